@@ -11,6 +11,34 @@ pub enum Attribute {
     Swiftness,
 }
 
+impl Attribute {
+    pub fn all() -> [Attribute; 4] {
+        use self::Attribute::*;
+        [Strength, Constitution, Endurance, Swiftness]
+    }
+    /// Name of the attribute as a stat, eg. "Strength".
+    pub fn name(&self) -> &'static str {
+        use self::Attribute::*;
+        match *self {
+            Strength => "Strength",
+            Constitution => "Constitution",
+            Endurance => "Endurance",
+            Swiftness => "Swiftness",
+        }
+    }
+    /// The attribute with the name, eg. "Strength", if any.
+    pub fn from_name(name: &str) -> Option<Attribute> {
+        use self::Attribute::*;
+        match name {
+            "Strength" => Some(Strength),
+            "Constitution" => Some(Constitution),
+            "Endurance" => Some(Endurance),
+            "Swiftness" => Some(Swiftness),
+            _ => None,
+        }
+    }
+}
+
 impl AsRef<Attribute> for Attribute {
     fn as_ref(&self) -> &Self {
         self
@@ -2,40 +2,141 @@ use super::*;
 
 impl CharacterBuilder {
     pub fn new<CA: AsRef<CharacterAttributes>>(
-        max_actions: usize,
         inventory_space: usize,
         attributes: CA,
     ) -> Self {
         let attributes = attributes.as_ref();
-        let life = attributes.get(&Attribute::Constitution);
-        Self {
+        let mut builder = Self {
             character: Character {
+                id: EntityId::new(),
                 base_attributes: attributes.clone(),
-                current_life: life,
+                current_life: 0,
+                current_stamina: 0,
                 equipment: EquipmentStore::default(),
                 name: String::new(),
-                action_buffer: ActionBuffer::new(max_actions),
                 inventory: Inventory::new(inventory_space),
-                available_actions: vec![Action::Attack]
+                available_actions: vec![Action::Attack],
+                progression: Progression::default(),
+                skills: vec![],
+                cooldowns: HashMap::new(),
+                queued_skills: vec![],
+                queued_consumables: vec![],
+                timed_modifiers: vec![],
+                base_stats: HashMap::new(),
+                stats: StatRegistry::default(),
+                sets: EquipmentSetLibrary::default(),
             },
-        }
+            equipment: vec![],
+            items: vec![],
+        };
+        builder.character.current_life = builder.character.max_life();
+        builder.character.current_stamina = builder.character.max_stamina();
+        builder
     }
     pub fn named(mut self, name: &str) -> Self {
         self.character.name = name.to_owned();
         self
     }
     pub fn add_slot<S: AsRef<Slot>>(mut self, slot: S) -> Self {
-        self.character
-            .equipment
-            .inner_mut()
-            .push((slot.as_ref().clone(), None));
+        self.character.equipment.add_slot(*slot.as_ref());
+        self
+    }
+    /// Sets the base value of a primary stat, eg. "Luck".
+    pub fn stat(mut self, name: &str, value: i32) -> Self {
+        self.character.set_base_stat(name, value);
+        self
+    }
+    /// Replaces the default equipment slots.
+    pub fn slots(mut self, slots: &[Slot]) -> Self {
+        self.character.equipment = EquipmentStore::new(slots);
+        self
+    }
+    /// Sets how much experience is required for each level and how many
+    /// attribute points are awarded per level.
+    pub fn progression(mut self, curve: LevelCurve, points_per_level: u32) -> Self {
+        let growth = self.character.progression.growth().clone();
+        self.character.progression = Progression::new(curve, points_per_level);
+        self.character.progression.set_growth(growth);
+        self
+    }
+    /// Raises the base attribute by the amount on every level-up.
+    pub fn growth(mut self, attribute: Attribute, amount: i32) -> Self {
+        let mut growth = self.character.progression.growth().clone();
+        growth.insert(attribute, amount);
+        self.character.progression.set_growth(growth);
+        self
+    }
+    /// Sets how Swiftness is converted into actions per round. Replaces the
+    /// definition of the "ActionsPerRound" stat, which fails if Swiftness is
+    /// derived from the number of actions.
+    pub fn action_formula(mut self, formula: ActionFormula) -> Result<Self, StatError> {
+        let actions = StatDefinition::derived(
+            DerivedStat::ActionsPerRound.name(),
+            formula.formula().source(),
+        );
+        self.character.stats.define(actions)?;
+        Ok(self)
+    }
+    /// Uses the stats of the registry, eg. loaded from a data file. Stats the
+    /// character already has, eg. "MaxLife", are kept unless the registry
+    /// defines them. Fails if the combined stats are not valid, eg. when they
+    /// depend on each other in a cycle.
+    pub fn stats(mut self, registry: &StatRegistry) -> Result<Self, StatError> {
+        let mut stats = registry.stats().to_vec();
+        for stat in self.character.stats.stats() {
+            if registry.get(&stat.name).is_none() {
+                stats.push(stat.clone());
+            }
+        }
+        let (old_max_life, old_max_stamina) =
+            (self.character.max_life(), self.character.max_stamina());
+        self.character.stats = StatRegistry::new(stats)?;
+        self.character.adapt_to_new_maximums(old_max_life, old_max_stamina);
+        Ok(self)
+    }
+    /// Uses the set definitions of the library for set bonuses, eg. loaded
+    /// from a data file.
+    pub fn sets(mut self, library: &EquipmentSetLibrary) -> Self {
+        let (old_max_life, old_max_stamina) =
+            (self.character.max_life(), self.character.max_stamina());
+        self.character.sets = library.clone();
+        self.character.adapt_to_new_maximums(old_max_life, old_max_stamina);
         self
     }
+    /// Starts with the skill already learned, regardless of its prerequisites.
+    pub fn skill(mut self, skill: &Skill) -> Self {
+        self.character.skills.push(skill.clone());
+        self
+    }
+    /// Starts with a copy of the item equipped. Items that cannot be worn are left out.
+    pub fn equip(mut self, item: &Equipment) -> Self {
+        self.equipment.push(item.clone());
+        self
+    }
+    /// Starts with a copy of the item in the inventory. Items that do not fit are left out.
+    pub fn carry(mut self, item: &Item) -> Self {
+        self.items.push(item.clone());
+        self
+    }
+    /// Builds a new character with its own identifier. Every character gets
+    /// their own copies of the starting items.
     pub fn build(&self) -> Character {
-        self.character.clone()
+        let mut character = self.character.clone();
+        character.id = EntityId::new();
+        for item in &self.equipment {
+            let _ = character.equip(item.spawn());
+        }
+        for item in &self.items {
+            character.inventory.put(item.spawn());
+        }
+        character
     }
 }
 
 pub struct CharacterBuilder {
     character: Character,
+    /// Starting equipment.
+    equipment: Vec<Equipment>,
+    /// Starting items in the inventory.
+    items: Vec<Item>,
 }
@@ -0,0 +1,110 @@
+use super::*;
+use serde_yaml;
+use skill::SkillLibrary;
+
+/// An archetype that characters are created from, eg. a warrior or a rogue.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CharacterClass {
+    /// Used to refer to the class, eg. "warrior".
+    pub id: String,
+    pub name: String,
+    /// Base attributes at level 1.
+    pub attributes: CharacterAttributes,
+    /// Base attributes raised automatically on every level-up.
+    #[serde(default)]
+    pub growth: HashMap<Attribute, i32>,
+    /// Equipment slots of the class, the default slots if not set.
+    #[serde(default)]
+    pub slots: Option<Vec<Slot>>,
+    #[serde(default = "default_inventory_space")]
+    pub inventory_space: usize,
+    /// Equipment worn at the start.
+    #[serde(default)]
+    pub equipment: Vec<Equipment>,
+    /// Items carried in the inventory at the start.
+    #[serde(default)]
+    pub inventory: Vec<Item>,
+    /// Names of the skills known at the start.
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+fn default_inventory_space() -> usize {
+    8
+}
+
+/// All class definitions of the game, usually loaded from data files.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClassLibrary {
+    classes: Vec<CharacterClass>,
+}
+
+impl ClassLibrary {
+    pub fn new(classes: Vec<CharacterClass>) -> ClassLibrary {
+        ClassLibrary { classes }
+    }
+    /// Loads a YAML list of classes.
+    pub fn from_yaml(yaml: &str) -> Result<ClassLibrary, serde_yaml::Error> {
+        Ok(ClassLibrary::new(serde_yaml::from_str(yaml)?))
+    }
+    pub fn get(&self, id: &str) -> Option<&CharacterClass> {
+        self.classes.iter().find(|class| class.id == id)
+    }
+    pub fn classes(&self) -> &[CharacterClass] {
+        self.classes.as_slice()
+    }
+}
+
+/// Reasons why a character could not be created from a class.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ClassError {
+    UnknownClass(String),
+    /// The skill library has no skill with the name.
+    UnknownSkill(String),
+    /// The starting equipment cannot be worn, or it displaces other starting equipment.
+    CannotEquip(String),
+    /// The starting items do not fit in the inventory.
+    InventoryFull(String),
+}
+
+impl CharacterBuilder {
+    /// Starts building a character of the class. The starting loadout is
+    /// checked up front so that building the character never loses items.
+    pub fn from_class(
+        id: &str,
+        classes: &ClassLibrary,
+        skills: &SkillLibrary,
+    ) -> Result<CharacterBuilder, ClassError> {
+        let class = classes
+            .get(id)
+            .ok_or_else(|| ClassError::UnknownClass(id.to_owned()))?;
+        let mut builder = CharacterBuilder::new(class.inventory_space, &class.attributes);
+        if let Some(ref slots) = class.slots {
+            builder = builder.slots(slots);
+        }
+        for (&attribute, &amount) in &class.growth {
+            builder = builder.growth(attribute, amount);
+        }
+        for name in &class.skills {
+            match skills.get(name) {
+                Some(skill) => builder = builder.skill(skill),
+                None => return Err(ClassError::UnknownSkill(name.clone())),
+            }
+        }
+        let mut trial = builder.build();
+        for item in &class.equipment {
+            match trial.equip(item.clone()) {
+                Ok(ref displaced) if displaced.is_empty() => {}
+                _ => return Err(ClassError::CannotEquip(item.name())),
+            }
+            builder = builder.equip(item);
+        }
+        for item in &class.inventory {
+            if trial.inventory.put(item.clone()).is_none() {
+                return Err(ClassError::InventoryFull(item.name()));
+            }
+            builder = builder.carry(item);
+        }
+        Ok(builder)
+    }
+}
@@ -0,0 +1,103 @@
+use super::*;
+
+/// Life gained from each point of Constitution.
+pub const LIFE_PER_CONSTITUTION: i32 = 1;
+/// Stamina gained from each point of Endurance.
+pub const STAMINA_PER_ENDURANCE: i32 = 1;
+
+/// Stats that the library itself uses, see the default `StatRegistry` for
+/// how they are computed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum DerivedStat {
+    MaxLife,
+    MaxStamina,
+    ActionsPerRound,
+    /// Damage of the weapon the character hits with, provided by the character.
+    WeaponDamage,
+    /// Total damage of a hit.
+    Damage,
+    CarryLimit,
+}
+
+impl DerivedStat {
+    /// Name of the stat in the registry and in item effects, eg. "MaxLife".
+    pub fn name(&self) -> &'static str {
+        match *self {
+            DerivedStat::MaxLife => "MaxLife",
+            DerivedStat::MaxStamina => "MaxStamina",
+            DerivedStat::ActionsPerRound => "ActionsPerRound",
+            DerivedStat::WeaponDamage => "WeaponDamage",
+            DerivedStat::Damage => "Damage",
+            DerivedStat::CarryLimit => "CarryLimit",
+        }
+    }
+}
+
+impl Character {
+    /// Value of the stat as defined by the character's stat registry, 0 if
+    /// the registry leaves it out.
+    pub fn derived(&self, stat: DerivedStat) -> i32 {
+        self.derived_in(stat, Scope::All)
+    }
+    pub(super) fn derived_in(&self, stat: DerivedStat, scope: Scope) -> i32 {
+        self.stat_in(stat.name(), scope).unwrap_or(0)
+    }
+    /// At least one.
+    pub fn max_life(&self) -> i32 {
+        max(self.derived(DerivedStat::MaxLife), 1)
+    }
+    pub fn max_stamina(&self) -> i32 {
+        max(self.derived(DerivedStat::MaxStamina), 0)
+    }
+    /// Used by conditional modifiers, which can't depend on themselves.
+    pub(super) fn max_life_without_conditionals(&self) -> i32 {
+        max(self.derived_in(DerivedStat::MaxLife, Scope::Unconditional), 1)
+    }
+    pub(super) fn max_stamina_without_conditionals(&self) -> i32 {
+        max(self.derived_in(DerivedStat::MaxStamina, Scope::Unconditional), 0)
+    }
+    /// Actions per round after the penalty for a heavy load, at least one.
+    pub fn actions_per_round(&self) -> usize {
+        let actions = max(self.derived(DerivedStat::ActionsPerRound), 0) as usize;
+        max(actions.saturating_sub(self.encumbrance().action_penalty()), 1)
+    }
+    pub fn stamina(&self) -> i32 {
+        self.current_stamina
+    }
+    /// Restores life up to the maximum. Returns the amount actually healed.
+    pub fn heal(&mut self, amount: i32) -> i32 {
+        let before = self.current_life;
+        self.current_life = min(self.current_life + max(amount, 0), self.max_life());
+        self.current_life - before
+    }
+    /// Restores stamina up to the maximum. Returns the amount actually restored.
+    pub fn restore_stamina(&mut self, amount: i32) -> i32 {
+        let before = self.current_stamina;
+        self.current_stamina = min(self.current_stamina + max(amount, 0), self.max_stamina());
+        self.current_stamina - before
+    }
+    /// Spends stamina if there's enough left. The amount is raised by
+    /// encumbrance, see `stamina_cost`. Returns false if there's not enough.
+    pub fn spend_stamina(&mut self, amount: i32) -> bool {
+        let cost = self.stamina_cost(amount);
+        if cost > self.current_stamina {
+            return false;
+        }
+        self.current_stamina -= cost;
+        true
+    }
+    /// Keeps life and stamina consistent with their maximums after the
+    /// maximums have changed, eg. when gear is changed. A raised maximum also
+    /// raises the current value by the same amount, a lowered maximum clamps
+    /// the current value but never knocks the character out.
+    pub(super) fn adapt_to_new_maximums(&mut self, old_max_life: i32, old_max_stamina: i32) {
+        let max_life = self.max_life();
+        if self.current_life > 0 {
+            let life = self.current_life + max(max_life - old_max_life, 0);
+            self.current_life = max(min(life, max_life), 1);
+        }
+        let max_stamina = self.max_stamina();
+        let stamina = self.current_stamina + max(max_stamina - old_max_stamina, 0);
+        self.current_stamina = max(min(stamina, max_stamina), 0);
+    }
+}
@@ -0,0 +1,108 @@
+use super::*;
+use item::HoldsItems;
+
+/// Weight that can be carried without penalties for each point of Strength.
+pub const CARRY_LIMIT_PER_STRENGTH: u32 = 10;
+/// Loads up to this many percent of the carry limit make a character burdened, heavier loads overburdened.
+pub const BURDENED_LIMIT_PERCENT: u32 = 150;
+
+/// How badly a character is slowed down by what it carries.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum Encumbrance {
+    Unburdened,
+    Burdened,
+    Overburdened,
+}
+
+impl Encumbrance {
+    /// Encumbrance with the given total weight and carry limit.
+    pub fn for_load(weight: u32, carry_limit: u32) -> Encumbrance {
+        if weight <= carry_limit {
+            Encumbrance::Unburdened
+        } else if weight as u64 * 100 <= carry_limit as u64 * BURDENED_LIMIT_PERCENT as u64 {
+            Encumbrance::Burdened
+        } else {
+            Encumbrance::Overburdened
+        }
+    }
+    /// Subtracted from Swiftness. Penalties stack, so a heavier load is never
+    /// better than a lighter one.
+    pub fn swiftness_penalty(&self) -> i32 {
+        match *self {
+            Encumbrance::Unburdened => 0,
+            Encumbrance::Burdened | Encumbrance::Overburdened => 2,
+        }
+    }
+    /// Subtracted from the number of actions per round, which never goes below
+    /// one. Overburdened characters lose an action on top of the Swiftness penalty.
+    pub fn action_penalty(&self) -> usize {
+        match *self {
+            Encumbrance::Overburdened => 1,
+            _ => 0,
+        }
+    }
+    /// Stamina costs in percent of the normal cost.
+    pub fn stamina_cost_percent(&self) -> i32 {
+        match *self {
+            Encumbrance::Unburdened => 100,
+            Encumbrance::Burdened => 150,
+            Encumbrance::Overburdened => 200,
+        }
+    }
+}
+
+/// What a character carries compared to what it can carry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Load {
+    /// Total weight of the worn and carried items.
+    pub weight: u32,
+    pub carry_limit: u32,
+    pub encumbrance: Encumbrance,
+}
+
+impl Load {
+    fn new(weight: u32, carry_limit: u32) -> Load {
+        Load {
+            weight,
+            carry_limit,
+            encumbrance: Encumbrance::for_load(weight, carry_limit),
+        }
+    }
+    /// Weight that can still be added without any penalties.
+    pub fn remaining(&self) -> u32 {
+        self.carry_limit.saturating_sub(self.weight)
+    }
+}
+
+impl Character {
+    /// Weight that can be carried without penalties, derived from Strength by
+    /// default. The penalties of a heavy load do not affect the carry limit.
+    pub fn carry_limit(&self) -> u32 {
+        max(self.derived_in(DerivedStat::CarryLimit, Scope::Unloaded), 0) as u32
+    }
+    /// The current load, including both worn and carried items.
+    pub fn load(&self) -> Load {
+        let worn: u32 = self
+            .equipment
+            .items
+            .iter()
+            .filter_map(|(_, item)| item.as_ref())
+            .map(|item| item.weight())
+            .sum();
+        let carried: u32 = self.inventory.iter().map(|entry| entry.item.weight()).sum();
+        Load::new(worn + carried, self.carry_limit())
+    }
+    /// The load after picking up the item, eg. to warn before the character becomes overburdened.
+    pub fn load_with(&self, item: &Item) -> Load {
+        let load = self.load();
+        Load::new(load.weight + item.weight(), load.carry_limit)
+    }
+    pub fn encumbrance(&self) -> Encumbrance {
+        self.load().encumbrance
+    }
+    /// The stamina cost of an action after encumbrance, rounded up.
+    pub fn stamina_cost(&self, base_cost: i32) -> i32 {
+        let cost = max(base_cost, 0) * self.encumbrance().stamina_cost_percent();
+        (cost + 99) / 100
+    }
+}
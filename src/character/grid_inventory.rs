@@ -0,0 +1,286 @@
+use item::{Entry, Footprint, HoldsItems, Item};
+
+/// Where an item lies in a grid inventory.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Placement {
+    /// The cell of the top-left corner of the item.
+    pub position: usize,
+    /// True if the item is turned by 90 degrees.
+    pub rotated: bool,
+}
+
+/// Holds items in a grid of cells where each item covers a rectangle given by
+/// its footprint. Positions are cell indices in row-major order, ie.
+/// `y * width + x`, and any cell covered by an item refers to that item.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct GridInventory {
+    width: usize,
+    height: usize,
+    // Contains all items that are currently stored in this inventory.
+    items: Vec<Item>,
+    // Placements of the items in the same order as the items.
+    placements: Vec<Placement>,
+    // Tells which cells are covered by items. Some() cells refer to the index in the items vector.
+    cells: Vec<Option<usize>>,
+}
+
+impl GridInventory {
+    pub fn new(width: usize, height: usize) -> GridInventory {
+        GridInventory {
+            width,
+            height,
+            items: vec![],
+            placements: vec![],
+            cells: vec![None; width * height],
+        }
+    }
+    pub fn width(&self) -> usize {
+        self.width
+    }
+    pub fn height(&self) -> usize {
+        self.height
+    }
+    /// Position of the cell at the column and the row.
+    pub fn position(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+    /// Placement of the item covering the cell.
+    pub fn placement(&self, pos: usize) -> Option<Placement> {
+        match self.cells.get(pos) {
+            Some(&Some(idx)) => Some(self.placements[idx]),
+            _ => None,
+        }
+    }
+    /// Returns true if an item with the footprint could be put at the position.
+    pub fn fits_at(&self, footprint: Footprint, pos: usize) -> bool {
+        match self.covered_cells(footprint, pos) {
+            Some(cells) => cells.iter().all(|&cell| self.cells[cell].is_none()),
+            None => false,
+        }
+    }
+    /// Puts the item with its top-left corner at the position. Returns false if there's no room.
+    pub fn put_at(&mut self, item: Item, pos: usize, rotated: bool) -> bool {
+        let footprint = oriented(item.footprint(), rotated);
+        if !self.fits_at(footprint, pos) {
+            return false;
+        }
+        let idx = self.items.len();
+        for cell in self.covered_cells(footprint, pos).unwrap() {
+            self.cells[cell] = Some(idx);
+        }
+        self.items.push(item);
+        self.placements.push(Placement {
+            position: pos,
+            rotated,
+        });
+        true
+    }
+    /// Finds the placement for the footprint that leaves the least fragmented
+    /// free space, ie. the one whose edges touch the most walls and items.
+    /// Prefers earlier positions and the unrotated orientation on ties.
+    pub fn best_fit(&self, footprint: Footprint) -> Option<Placement> {
+        let mut best: Option<(usize, Placement)> = None;
+        for pos in 0..self.cells.len() {
+            for &rotated in &[false, true] {
+                let oriented = oriented(footprint, rotated);
+                if !self.fits_at(oriented, pos) {
+                    continue;
+                }
+                let contact = self.contact(oriented, pos);
+                if best.is_none_or(|(most, _)| contact > most) {
+                    best = Some((
+                        contact,
+                        Placement {
+                            position: pos,
+                            rotated,
+                        },
+                    ));
+                }
+            }
+        }
+        best.map(|(_, placement)| placement)
+    }
+    /// Moves the item covering `from` so that its top-left corner is at `to`.
+    /// Returns false and leaves the item in place if there's no room.
+    pub fn move_item(&mut self, from: usize, to: usize, rotated: bool) -> bool {
+        let idx = match self.cells.get(from) {
+            Some(&Some(idx)) => idx,
+            _ => return false,
+        };
+        let footprint = oriented(self.items[idx].footprint(), rotated);
+        self.clear_cells(idx);
+        if !self.fits_at(footprint, to) {
+            self.fill_cells(idx);
+            return false;
+        }
+        self.placements[idx] = Placement {
+            position: to,
+            rotated,
+        };
+        self.fill_cells(idx);
+        true
+    }
+    /// Turns the item covering the position by 90 degrees around its top-left corner.
+    pub fn rotate(&mut self, pos: usize) -> bool {
+        match self.placement(pos) {
+            Some(placement) => self.move_item(pos, placement.position, !placement.rotated),
+            None => false,
+        }
+    }
+    /// Swaps the places of the items covering the positions, keeping their
+    /// orientations. Returns false and changes nothing if either item does not fit.
+    pub fn swap(&mut self, pos_1: usize, pos_2: usize) -> bool {
+        let (idx_1, idx_2) = match (self.cells.get(pos_1), self.cells.get(pos_2)) {
+            (Some(&Some(idx_1)), Some(&Some(idx_2))) => (idx_1, idx_2),
+            _ => return false,
+        };
+        if idx_1 == idx_2 {
+            return true;
+        }
+        let (placement_1, placement_2) = (self.placements[idx_1], self.placements[idx_2]);
+        self.clear_cells(idx_1);
+        self.clear_cells(idx_2);
+        self.placements[idx_1].position = placement_2.position;
+        self.placements[idx_2].position = placement_1.position;
+        let footprint_1 = oriented(self.items[idx_1].footprint(), placement_1.rotated);
+        let footprint_2 = oriented(self.items[idx_2].footprint(), placement_2.rotated);
+        let fits = self.fits_at(footprint_1, placement_2.position) && {
+            self.fill_cells(idx_1);
+            let fits = self.fits_at(footprint_2, placement_1.position);
+            self.clear_cells(idx_1);
+            fits
+        };
+        if !fits {
+            self.placements[idx_1] = placement_1;
+            self.placements[idx_2] = placement_2;
+        }
+        self.fill_cells(idx_1);
+        self.fill_cells(idx_2);
+        fits
+    }
+    pub fn is_reserved(&self, pos: usize) -> bool {
+        self.cells[pos].is_some()
+    }
+    /// Cells covered by the footprint at the position. None if it would extend past the grid.
+    fn covered_cells(&self, footprint: Footprint, pos: usize) -> Option<Vec<usize>> {
+        if self.width == 0 {
+            return None;
+        }
+        let (x, y) = (pos % self.width, pos / self.width);
+        if footprint.area() == 0
+            || x + footprint.width > self.width
+            || y + footprint.height > self.height
+        {
+            return None;
+        }
+        let mut cells = vec![];
+        for dy in 0..footprint.height {
+            for dx in 0..footprint.width {
+                cells.push(self.position(x + dx, y + dy));
+            }
+        }
+        Some(cells)
+    }
+    /// Number of cell edges around the footprint that touch a wall or an item.
+    fn contact(&self, footprint: Footprint, pos: usize) -> usize {
+        let (x, y) = ((pos % self.width) as i64, (pos / self.width) as i64);
+        let (w, h) = (footprint.width as i64, footprint.height as i64);
+        let mut neighbours = vec![];
+        for dx in 0..w {
+            neighbours.push((x + dx, y - 1));
+            neighbours.push((x + dx, y + h));
+        }
+        for dy in 0..h {
+            neighbours.push((x - 1, y + dy));
+            neighbours.push((x + w, y + dy));
+        }
+        neighbours
+            .into_iter()
+            .filter(|&(nx, ny)| {
+                nx < 0
+                    || ny < 0
+                    || nx >= self.width as i64
+                    || ny >= self.height as i64
+                    || self.cells[self.position(nx as usize, ny as usize)].is_some()
+            })
+            .count()
+    }
+    fn clear_cells(&mut self, idx: usize) {
+        for cell in self.cells.iter_mut() {
+            if *cell == Some(idx) {
+                *cell = None;
+            }
+        }
+    }
+    fn fill_cells(&mut self, idx: usize) {
+        let placement = self.placements[idx];
+        let footprint = oriented(self.items[idx].footprint(), placement.rotated);
+        for cell in self.covered_cells(footprint, placement.position).unwrap() {
+            self.cells[cell] = Some(idx);
+        }
+    }
+}
+
+impl HoldsItems for GridInventory {
+    fn capacity(&self) -> usize {
+        self.width * self.height
+    }
+    /// Puts the item in the best fitting place. Returns the position of its top-left corner.
+    fn put(&mut self, item: Item) -> Option<usize> {
+        let placement = self.best_fit(item.footprint())?;
+        self.put_at(item, placement.position, placement.rotated);
+        Some(placement.position)
+    }
+    fn take(&mut self, pos: i32) -> Option<Item> {
+        let idx = match self.cells.get(pos as usize) {
+            Some(&Some(idx)) if pos >= 0 => idx,
+            _ => return None,
+        };
+        // Remove references to the item and decrease the indices of later items
+        for cell in self.cells.iter_mut() {
+            *cell = match *cell {
+                Some(i) if i == idx => None,
+                Some(i) if i > idx => Some(i - 1),
+                other => other,
+            };
+        }
+        self.placements.remove(idx);
+        Some(self.items.remove(idx))
+    }
+    fn get(&self, pos: i32) -> Option<&Item> {
+        match self.cells.get(pos as usize) {
+            Some(&Some(idx)) if pos >= 0 => Some(&self.items[idx]),
+            _ => None,
+        }
+    }
+    fn get_mut(&mut self, pos: i32) -> Option<&mut Item> {
+        match self.cells.get(pos as usize) {
+            Some(&Some(idx)) if pos >= 0 => Some(&mut self.items[idx]),
+            _ => None,
+        }
+    }
+    fn get_clone(&self, pos: i32) -> Option<Item> {
+        self.get(pos).cloned()
+    }
+    fn holds_id(&self, id: usize) -> bool {
+        self.items.len() > id
+    }
+    /// Iterates over the items in the order of their top-left corners.
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        let mut order: Vec<usize> = (0..self.items.len()).collect();
+        order.sort_by_key(|&idx| self.placements[idx].position);
+        Box::new(order.into_iter().map(move |idx| Entry {
+            position: self.placements[idx].position,
+            size: self.items[idx].footprint().area(),
+            item: &self.items[idx],
+        }))
+    }
+}
+
+fn oriented(footprint: Footprint, rotated: bool) -> Footprint {
+    if rotated {
+        footprint.rotated()
+    } else {
+        footprint
+    }
+}
@@ -1,7 +1,9 @@
-use std::cmp::max;
-use item::{Item, HoldsItems};
+use std::cmp::{max, min};
+use item::{Consumable, ContainerError, Entry, HoldsItems, Item};
+use Display;
+use entity::{Entity, EntityId};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Inventory {
     capacity: usize,
     // Contains all items that are currently stored in this inventory.
@@ -86,6 +88,242 @@ impl Inventory {
     pub fn is_reserved(&self, pos: usize) -> bool{
         self.positions[pos].is_some()
     }
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+    /// Number of free positions.
+    pub fn free_space(&self) -> usize {
+        self.positions.iter().filter(|pos| pos.is_none()).count()
+    }
+    /// How many more of the consumable would fit, counting both the room left
+    /// in existing stacks and new stacks in the free space.
+    pub fn room_for(&self, consumable: &Consumable) -> usize {
+        let in_stacks: usize = self
+            .items
+            .iter()
+            .filter_map(|item| match *item {
+                Item::Consumable(ref stack) if stack.stacks_with(consumable) => {
+                    Some(stack.stack_limit() - min(stack.count(), stack.stack_limit()))
+                }
+                _ => None,
+            })
+            .sum();
+        let new_stacks: usize = match consumable.size() {
+            0 => 0,
+            size => self
+                .positions
+                .split(|pos| pos.is_some())
+                .map(|free| free.len() / size)
+                .sum(),
+        };
+        in_stacks + new_stacks * consumable.stack_limit()
+    }
+    /// Takes some consumables from the stack at the position into a new stack.
+    /// Returns the position of the new stack, or None if the stack can't be
+    /// split or there's no room for the new stack.
+    pub fn split(&mut self, pos: usize, amount: usize) -> Option<usize> {
+        let (split, size) = match self.get(pos as i32) {
+            Some(Item::Consumable(stack)) => {
+                let mut stack = stack.clone();
+                (stack.split(amount)?, stack.size())
+            }
+            _ => return None,
+        };
+        let new_pos = self.find_space(size)?;
+        if let Some(&mut Item::Consumable(ref mut stack)) = self.get_mut(pos as i32) {
+            stack.split(amount);
+        }
+        self.put_at(split.into(), new_pos);
+        Some(new_pos)
+    }
+    /// Moves as many consumables as fit from the stack at `from` into the stack at `to`.
+    /// The emptied stack is removed. Returns false if the items do not stack.
+    pub fn merge(&mut self, from: usize, to: usize) -> bool {
+        let (from_idx, to_idx) = match (self.positions.get(from), self.positions.get(to)) {
+            (Some(&Some(from_idx)), Some(&Some(to_idx))) if from_idx != to_idx => {
+                (from_idx, to_idx)
+            }
+            _ => return false,
+        };
+        let source = match self.items[from_idx] {
+            Item::Consumable(ref source) => source.clone(),
+            _ => return false,
+        };
+        let rest = match self.items[to_idx] {
+            Item::Consumable(ref mut target) if target.stacks_with(&source) => target.merge(source),
+            _ => return false,
+        };
+        match rest {
+            Some(rest) => self.items[from_idx] = rest.into(),
+            None => {
+                self.take(from as i32);
+            }
+        }
+        true
+    }
+    /// Merges the consumable into existing stacks. Nothing is changed unless
+    /// everything fits, either into the stacks or into one new stack.
+    /// Returns the position of the last stack, or the consumable if it
+    /// needs a new stack.
+    fn put_into_stacks(&mut self, consumable: Consumable) -> Result<usize, Box<Consumable>> {
+        let stack_positions: Vec<usize> = self
+            .iter()
+            .filter(|entry| match *entry.item {
+                Item::Consumable(ref stack) => stack.stacks_with(&consumable),
+                _ => false,
+            })
+            .map(|entry| entry.position)
+            .collect();
+        let mut staged = self.items.clone();
+        let mut rest = consumable.clone();
+        for pos in stack_positions {
+            if let Item::Consumable(ref mut stack) = staged[self.positions[pos].unwrap()] {
+                match stack.merge(rest) {
+                    Some(left) => rest = left,
+                    None => {
+                        self.items = staged;
+                        return Ok(pos);
+                    }
+                }
+            }
+        }
+        if self.find_space(rest.size()).is_some() {
+            self.items = staged;
+            Err(Box::new(rest))
+        } else {
+            Err(Box::new(consumable))
+        }
+    }
+    /// The item at the path of positions through nested containers, eg.
+    /// `[2, 0]` is the first item in the container at position 2.
+    pub fn get_path(&self, path: &[usize]) -> Option<&Item> {
+        let (&pos, rest) = path.split_first()?;
+        let item = self.get(pos as i32)?;
+        if rest.is_empty() {
+            return Some(item);
+        }
+        match *item {
+            Item::Container(ref container) => container.contents().get_path(rest),
+            _ => None,
+        }
+    }
+    /// Searches this and all nested containers depth-first. Returns the paths
+    /// of the matching items, see `get_path`.
+    pub fn find<P: Fn(&Item) -> bool>(&self, predicate: &P) -> Vec<(Vec<usize>, &Item)> {
+        let mut found = vec![];
+        for entry in self.iter() {
+            if predicate(entry.item) {
+                found.push((vec![entry.position], entry.item));
+            }
+            if let Item::Container(ref container) = *entry.item {
+                for (mut path, item) in container.contents().find(predicate) {
+                    path.insert(0, entry.position);
+                    found.push((path, item));
+                }
+            }
+        }
+        found
+    }
+    /// Finds the item with the identifier in this and all nested containers.
+    /// Returns the path of the item, see `get_path`.
+    pub fn find_by_id(&self, id: EntityId) -> Option<(Vec<usize>, &Item)> {
+        self.find(&|item: &Item| item.id() == id).into_iter().next()
+    }
+    /// Moves the item at the path into the container at another path, or
+    /// into this inventory if `into` is empty. Nothing changes on failure.
+    /// Returns the position of the item in its new container.
+    pub fn move_item(&mut self, from: &[usize], into: &[usize]) -> Result<usize, ContainerError> {
+        let from = self.start_path(from).ok_or(ContainerError::NotFound)?;
+        let into = self.start_path(into).ok_or(ContainerError::NotFound)?;
+        let item = self.get_path(&from).ok_or(ContainerError::NotFound)?;
+        if into.starts_with(&from) {
+            return Err(ContainerError::IntoItself);
+        }
+        if !into.is_empty() {
+            match self.get_path(&into) {
+                Some(Item::Container(container)) => if !container.accepts(item) {
+                    return Err(ContainerError::Rejected);
+                },
+                _ => return Err(ContainerError::NotAContainer),
+            }
+        }
+
+        let mut staged = self.clone();
+        let (&pos, parent) = from.split_last().unwrap();
+        let item = staged.contents_at_mut(parent).unwrap().take(pos as i32).unwrap();
+        let new_pos = staged
+            .contents_at_mut(&into)
+            .unwrap()
+            .put(item)
+            .ok_or(ContainerError::NoRoom)?;
+        *self = staged;
+        Ok(new_pos)
+    }
+    /// Contents of the container at the path, or this inventory if the path is empty.
+    fn contents_at_mut(&mut self, path: &[usize]) -> Option<&mut Inventory> {
+        let (&pos, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Some(self),
+        };
+        match self.get_mut(pos as i32) {
+            Some(&mut Item::Container(ref mut container)) => {
+                container.contents_mut().contents_at_mut(rest)
+            }
+            _ => None,
+        }
+    }
+    /// The path with each position replaced by the start position of the item.
+    fn start_path(&self, path: &[usize]) -> Option<Vec<usize>> {
+        let (&pos, rest) = match path.split_first() {
+            Some(split) => split,
+            None => return Some(vec![]),
+        };
+        self.get(pos as i32)?;
+        let start = self.bounds(pos as i32).0;
+        let mut normalized = vec![start];
+        if !rest.is_empty() {
+            match *self.get(pos as i32)? {
+                Item::Container(ref container) => {
+                    normalized.extend(container.contents().start_path(rest)?)
+                }
+                _ => return None,
+            }
+        }
+        Some(normalized)
+    }
+    /// Moves all items to the start of the inventory, keeping their order, so
+    /// that the free space is in one piece at the end.
+    pub fn compact(&mut self) {
+        let order: Vec<usize> = self.iter().map(|entry| entry.position).collect();
+        self.rearrange(&order);
+    }
+    /// Sorts the items largest first, then by name, and compacts the inventory.
+    pub fn sort(&mut self) {
+        self.sort_by_key(|item| (::std::cmp::Reverse(item.size()), item.name()));
+    }
+    /// Sorts the items by the key and compacts the inventory.
+    pub fn sort_by_key<K: Ord, F: Fn(&Item) -> K>(&mut self, f: F) {
+        let mut entries: Vec<(usize, K)> = self
+            .iter()
+            .map(|entry| (entry.position, f(entry.item)))
+            .collect();
+        entries.sort_by(|a, b| a.1.cmp(&b.1));
+        let order: Vec<usize> = entries.into_iter().map(|(position, _)| position).collect();
+        self.rearrange(&order);
+    }
+    /// Places the items at the given start positions next to each other in that order.
+    fn rearrange(&mut self, order: &[usize]) {
+        let mut items: Vec<Option<Item>> = self.items.drain(..).map(Some).collect();
+        let old_positions = ::std::mem::replace(&mut self.positions, vec![None; self.capacity]);
+        let mut pos = 0;
+        for &start in order {
+            let idx = old_positions[start].expect("rearranged an empty position");
+            let item = items[idx].take().expect("rearranged an item twice");
+            let size = item.size();
+            self.put_at(item, pos);
+            pos += size;
+        }
+    }
 }
 
 impl HoldsItems for Inventory {
@@ -93,7 +331,18 @@ impl HoldsItems for Inventory {
         self.capacity
     }
     /// Returns the position into which the item was put. None if no room.
+    /// Consumables are merged into existing stacks first, in which case the
+    /// position of the last stack that was added to is returned.
     fn put(&mut self, item: Item) -> Option<usize> {
+        let item = match item {
+            // Empty stacks are not worth a place in the inventory
+            Item::Consumable(ref consumable) if consumable.count() == 0 => return None,
+            Item::Consumable(consumable) => match self.put_into_stacks(consumable) {
+                Ok(pos) => return Some(pos),
+                Err(rest) => Item::Consumable(*rest),
+            },
+            item => item,
+        };
         let pos = self.find_space(item.size());
         match pos {
             Some(i) => {
@@ -158,6 +407,22 @@ impl HoldsItems for Inventory {
     fn holds_id(&self, id: usize) -> bool {
         self.items.len() > id
     }
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        Box::new(
+            self.positions
+                .iter()
+                .enumerate()
+                // Only the first position of each item, items occupy contiguous positions
+                .filter(move |&(pos, &idx)| {
+                    idx.is_some() && (pos == 0 || self.positions[pos - 1] != idx)
+                })
+                .map(move |(pos, &idx)| Entry {
+                    position: pos,
+                    size: self.positions[pos..].iter().take_while(|&&p| p == idx).count(),
+                    item: &self.items[idx.unwrap()],
+                }),
+        )
+    }
     fn get_clone(&self, pos: i32) -> Option<Item> {
         if pos < 0 || pos >= self.capacity() as i32 {
             return None;
@@ -1,25 +1,80 @@
 mod attribute;
 mod inventory;
+mod grid_inventory;
 mod builder;
+mod class;
+mod progression;
+mod derived;
+mod encumbrance;
+mod modifier;
+mod requirements;
+mod sets;
+mod sheet;
+mod skills;
+mod stats;
+mod transfer;
+#[cfg(test)]
+mod tests;
 
 pub use self::attribute::*;
 pub use self::inventory::*;
+pub use self::grid_inventory::*;
 pub use self::builder::*;
+pub use self::class::*;
+pub use self::progression::*;
+pub use self::derived::*;
+pub use self::encumbrance::*;
+pub use self::modifier::*;
+pub use self::requirements::*;
+pub use self::sheet::*;
+pub use self::transfer::*;
 
 use super::item::*;
 use super::combat::*;
-use super::Display;
-use std::cmp::max;
+use super::{Display, NounKind};
+use entity::{Entity, EntityId};
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use skill::{Skill, SkillEffect};
+use stat::{StatDefinition, StatError, StatRegistry};
+use self::stats::Scope;
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Character {
+    #[serde(default)]
+    id: EntityId,
     base_attributes: CharacterAttributes,
     current_life: i32,
+    current_stamina: i32,
     equipment: EquipmentStore,
     name: String,
     available_actions: Vec<Action>,
-    action_buffer: ActionBuffer,
     pub inventory: Inventory,
+    progression: Progression,
+    /// Learned skills.
+    #[serde(default)]
+    skills: Vec<Skill>,
+    /// Rounds left before skills can be used again.
+    #[serde(default)]
+    cooldowns: HashMap<String, u32>,
+    /// Skills to use in the next combat round.
+    #[serde(default)]
+    queued_skills: Vec<String>,
+    /// Inventory positions of consumables to use in the next combat round.
+    #[serde(default)]
+    queued_consumables: Vec<usize>,
+    /// Buffs and debuffs.
+    #[serde(default)]
+    timed_modifiers: Vec<TimedModifier>,
+    /// Base values of primary stats that are not attributes, eg. "Luck".
+    #[serde(default)]
+    base_stats: HashMap<String, i32>,
+    /// Definitions of the character's stats, eg. how maximum life is computed.
+    #[serde(default)]
+    stats: StatRegistry,
+    /// Definitions of the sets that worn equipment may be a piece of.
+    #[serde(default)]
+    sets: EquipmentSetLibrary,
 }
 
 impl Character {
@@ -32,37 +87,32 @@ impl Character {
     pub fn equipment(&self) -> &EquipmentStore {
         &self.equipment
     }
-    /// Returns what was equipped previously.
-    pub fn equip(&mut self, item: Equipment) -> Option<Equipment> {
-        self.equipment.equip(item)
+    /// Equips the item, displacing everything in the slots it needs. Returns
+    /// all displaced items, or an error if the character cannot wear the item.
+    pub fn equip(&mut self, item: Equipment) -> Result<Vec<Equipment>, EquipError> {
+        let unmet = self.unmet_requirements(&item);
+        if !unmet.is_empty() {
+            return Err(EquipError::UnmetRequirements {
+                item: Box::new(item),
+                unmet,
+            });
+        }
+        let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+        let displaced = self.equipment.equip(item).map_err(EquipError::NoSuitableSlots)?;
+        self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+        Ok(displaced)
     }
     /// Unequips and returns the unequipped item.
-    /// Takes a slot and an identifier in case there's more slots of same type.
+    /// Takes the index of any of the slots the item occupies.
     pub fn unequip(&mut self, slot_idx: usize) -> Option<Equipment> {
-        self.equipment.unequip(slot_idx)
+        let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+        let unequipped = self.equipment.unequip(slot_idx);
+        self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+        unequipped
     }
+    /// Base value with all modifiers that currently apply, see `attribute_breakdown`.
     pub fn attribute(&self, attr: &Attribute) -> i32 {
-        // Innate ability of character
-        let base = self.base_attributes.get(attr);
-
-        // Combined bonuses from worn items
-        let mut from_items = 0;
-        for &(_, ref item) in &self.equipment.items {
-            match *item {
-                None => {
-                    continue;
-                }
-                Some(ref item) => for effect in item.effects() {
-                    match effect {
-                        ItemEffect::AttributeModifier(ref id, amount) => if id == attr {
-                            from_items += amount;
-                        },
-                    }
-                },
-            }
-        }
-
-        base + from_items
+        self.attribute_breakdown(*attr).total
     }
     pub fn nth_slot(&self, n: usize) -> Option<&Slot> {
         self.equipment.nth_slot(n)
@@ -72,97 +122,142 @@ impl Character {
 impl Default for Character {
     fn default() -> Self {
         Self {
+            id: EntityId::new(),
             base_attributes: CharacterAttributes::default(),
             current_life: 1,
-            equipment: EquipmentStore {
-                items: vec![
-                    (Slot::Head, None),
-                    (Slot::Hand, None),
-                    (Slot::Torso, None),
-                    (Slot::Hand, None),
-                    (Slot::Feet, None),
-                ],
-            },
+            current_stamina: 1,
+            equipment: EquipmentStore::new(&[
+                Slot::Head,
+                Slot::Hand,
+                Slot::Torso,
+                Slot::Hand,
+                Slot::Feet,
+            ]),
             name: String::new(),
-            action_buffer: ActionBuffer::default(),
             inventory: Inventory::new(8),
             available_actions: vec![Action::Attack],
+            progression: Progression::default(),
+            skills: vec![],
+            cooldowns: HashMap::new(),
+            queued_skills: vec![],
+            queued_consumables: vec![],
+            timed_modifiers: vec![],
+            base_stats: HashMap::new(),
+            stats: StatRegistry::default(),
+            sets: EquipmentSetLibrary::default(),
         }
     }
 }
 
-#[derive(Clone)]
+/// Reasons why an item could not be equipped. The item is returned with the error.
+#[derive(Clone, Debug)]
+pub enum EquipError {
+    /// The character lacks slots of the types the item needs.
+    NoSuitableSlots(Box<Equipment>),
+    /// The character's base attributes are too low.
+    UnmetRequirements {
+        item: Box<Equipment>,
+        unmet: Vec<UnmetRequirement>,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct EquipmentStore {
+    /// Items are stored in the first slot they occupy.
     items: Vec<(Slot, Option<Equipment>)>,
+    /// The other slots occupied by multi-slot items refer to the slot the item is stored in.
+    occupied_by: Vec<Option<usize>>,
 }
 
 impl EquipmentStore {
-    pub fn equip(&mut self, item: Equipment) -> Option<Equipment> {
-        let first_free = self.first_free(item.slot());
-        match first_free {
-            // Available slot: equip in that slot
-            Some(idx) => {
-                let (_, ref mut slot) = self.items[idx];
-                *slot = Some(item);
-                None
-            }
-            // No available slot: unequip something
-            None => {
-                let first = self.first(item.slot());
-                match first {
-                    Some(idx) => {
-                        let (_, ref mut slot) = self.items[idx];
-                        let prev_equip = slot.clone();
-                        *slot = Some(item);
-                        prev_equip
-                    }
-                    None => None,
-                }
+    pub fn new(slots: &[Slot]) -> EquipmentStore {
+        EquipmentStore {
+            items: slots.iter().map(|&slot| (slot, None)).collect(),
+            occupied_by: vec![None; slots.len()],
+        }
+    }
+    pub fn add_slot(&mut self, slot: Slot) {
+        self.items.push((slot, None));
+        self.occupied_by.push(None);
+    }
+    /// Equips the item into the slots it needs, preferring free slots. Returns
+    /// every item that had to be unequipped to make room, or the item itself if
+    /// there are not enough slots of the right types.
+    pub fn equip(&mut self, item: Equipment) -> Result<Vec<Equipment>, Box<Equipment>> {
+        let targets = match self.target_slots(&item) {
+            Some(targets) => targets,
+            None => return Err(Box::new(item)),
+        };
+
+        // Make room
+        let mut displaced = vec![];
+        for &idx in &targets {
+            if let Some(previous) = self.unequip(idx) {
+                displaced.push(previous);
             }
         }
+
+        // Store the item in the first slot, reserve the rest
+        let (first, rest) = targets.split_first().unwrap();
+        self.items[*first].1 = Some(item);
+        for &idx in rest {
+            self.occupied_by[idx] = Some(*first);
+        }
+        Ok(displaced)
     }
+    /// Unequips the item occupying the slot, freeing every slot the item occupies.
     pub fn unequip(&mut self, slot_idx: usize) -> Option<Equipment> {
-        // Vec<(Slot, Option<Equipment>)>
-        // &mut (Slot, Option<Equipment>)
-        let stored = match self.items[slot_idx] {
-            (_, None) => None,
-            (_, Some(ref item)) => Some(item.clone()),
-        };
-        match stored {
-            None => None,
-            Some(ret) => {
-                let slot = &mut self.items[slot_idx];
-                *slot = (slot.0, None);
-                Some(ret)
+        let owner = self.owner_of(slot_idx)?;
+        for occupant in self.occupied_by.iter_mut() {
+            if *occupant == Some(owner) {
+                *occupant = None;
             }
         }
+        self.items[owner].1.take()
     }
-    fn first_free(&self, slot: &Slot) -> Option<usize> {
+    /// Index of the slot in which the equipment with the identifier is stored.
+    pub fn find_by_id(&self, id: EntityId) -> Option<usize> {
         self.items
             .iter()
-            .position(|&(ref s, ref option)| s == slot && option.is_none())
+            .position(|(_, item)| item.as_ref().map(|item| item.id()) == Some(id))
     }
-    fn first(&self, slot: &Slot) -> Option<usize> {
-        self.items.iter().position(|&(ref s, _)| s == slot)
+    /// Returns true if there are slots of every type the item needs.
+    pub fn fits(&self, item: &Equipment) -> bool {
+        self.target_slots(item).is_some()
     }
-    fn first_reserved(&self, slot: &Slot) -> Option<usize> {
-        self.items
-            .iter()
-            .position(|&(ref s, ref option)| s == slot && option.is_some())
+    /// Returns true if the slot holds no item and is not reserved by a multi-slot item.
+    pub fn is_free(&self, slot_idx: usize) -> bool {
+        self.owner_of(slot_idx).is_none()
     }
-    pub fn first_in_slot(&self, slot: &Slot) -> Option<&Equipment> {
-        let first = self.first_reserved(slot);
-        match first {
+    /// Index of the slot in which the item occupying the given slot is stored.
+    pub fn owner_of(&self, slot_idx: usize) -> Option<usize> {
+        match self.items.get(slot_idx) {
             None => None,
-            Some(idx) => {
-                // items: Vec<(Slot, Option<Equipment>)>
-                let (_, ref slot) = self.items[idx];
-                match *slot {
-                    Some(ref equipment) => Some(equipment),
-                    None => unreachable!(),
-                }
+            Some(&(_, Some(_))) => Some(slot_idx),
+            Some(&(_, None)) => self.occupied_by[slot_idx],
+        }
+    }
+    /// Picks a slot for each slot type the item needs, preferring free slots.
+    fn target_slots(&self, item: &Equipment) -> Option<Vec<usize>> {
+        let mut targets: Vec<usize> = vec![];
+        for slot in item.slots() {
+            let candidates: Vec<usize> = (0..self.items.len())
+                .filter(|&idx| self.items[idx].0 == slot && !targets.contains(&idx))
+                .collect();
+            let free = candidates.iter().cloned().find(|&idx| self.is_free(idx));
+            match free.or_else(|| candidates.first().cloned()) {
+                Some(idx) => targets.push(idx),
+                None => return None,
             }
         }
+        Some(targets)
+    }
+    /// The item occupying the slot, also if it is stored in another slot.
+    fn occupant(&self, slot_idx: usize) -> Option<&Equipment> {
+        self.owner_of(slot_idx).and_then(|owner| self.at(owner))
+    }
+    pub fn first_in_slot(&self, slot: &Slot) -> Option<&Equipment> {
+        self.by_slot(slot).first().cloned()
     }
     pub fn nth_slot(&self, n: usize) -> Option<&Slot> {
         let entry = self.items.iter().nth(n);
@@ -171,36 +266,34 @@ impl EquipmentStore {
             Some(&(ref slot, _)) => Some(slot),
         }
     }
+    /// The item occupying the nth slot of the type. A multi-slot item is
+    /// found through every slot it occupies.
     pub fn nth_in_slot(&self, slot: &Slot, n: usize) -> Option<&Equipment> {
-        let slot_option = self.items.iter().filter(|&&(ref s, _)| s == slot).nth(n);
-        match slot_option {
-            None => None,
-            Some(&(_, ref o_item)) => match *o_item {
-                None => None,
-                Some(ref item) => Some(item),
-            },
-        }
+        let idx = (0..self.items.len()).filter(|&idx| self.items[idx].0 == *slot).nth(n)?;
+        self.occupant(idx)
     }
+    /// Items occupying slots of the type, each multi-slot item only once.
     pub fn by_slot(&self, slot: &Slot) -> Vec<&Equipment> {
-        self.items.iter()
-            // Filter to slots of correct type
-            .filter(|&&(ref s, _)| s == slot)
-            // Filter to slots containing some
-            .filter(|&&(_, ref item)| item.is_some())
-            // TODO: can be made more concise by using Option<T>.as_ref() -> Option<&T>s
-            .map(|&(_, ref item_option)| match *item_option {
-                None => None,
-                Some(ref item) => Some(item),
-            }.unwrap())
-            // Return references
+        let mut owners: Vec<usize> = (0..self.items.len())
+            .filter(|&idx| self.items[idx].0 == *slot)
+            .filter_map(|idx| self.owner_of(idx))
+            .collect();
+        owners.sort();
+        owners.dedup();
+        owners.iter().filter_map(|&owner| self.at(owner)).collect()
+    }
+    /// Every slot and the item occupying it. Multi-slot items appear in each
+    /// slot they occupy.
+    pub fn inner(&self) -> Vec<(Slot, Option<&Equipment>)> {
+        (0..self.items.len())
+            .map(|idx| (self.items[idx].0, self.occupant(idx)))
             .collect()
     }
+    /// Direct access to the stored items.
+    #[deprecated(note = "bypasses the slots reserved by multi-slot items, use equip and unequip")]
     pub fn inner_mut(&mut self) -> &mut Vec<(Slot, Option<Equipment>)> {
         &mut self.items
     }
-    pub fn inner(&self) -> &Vec<(Slot, Option<Equipment>)> {
-        &self.items
-    }
     pub fn slots(&self) -> Vec<&Slot> {
         self.items.iter().map(|&(ref slot, _)| slot).collect()
     }
@@ -215,15 +308,7 @@ impl EquipmentStore {
 
 impl Default for EquipmentStore {
     fn default() -> Self {
-        EquipmentStore {
-            items: vec![
-                (Slot::Hand, None),
-                (Slot::Hand, None),
-                (Slot::Head, None),
-                (Slot::Torso, None),
-                (Slot::Feet, None),
-            ],
-        }
+        EquipmentStore::new(&[Slot::Hand, Slot::Hand, Slot::Head, Slot::Torso, Slot::Feet])
     }
 }
 
@@ -246,14 +331,36 @@ impl Combatant for Character {
         }
     }
     fn damage(&self) -> i32 {
-        let strength = self.attribute(&Attribute::Strength);
-        self.best_weapon().damage() + strength
+        self.derived(DerivedStat::Damage)
     }
+    /// Queued skills and consumables come first, the rest of the actions are attacks.
     fn action_buffer(&self) -> ActionBuffer {
-        ActionBuffer::default()
+        let mut buffer = ActionBuffer::new(self.actions_per_round());
+        for name in &self.queued_skills {
+            buffer.push(&Action::Skill(name.clone()));
+        }
+        for &position in &self.queued_consumables {
+            buffer.push(&Action::Consume(position));
+        }
+        while buffer.push(&Action::Attack) {}
+        buffer
+    }
+    fn use_skill(&mut self, name: &str) -> Option<Vec<SkillEffect>> {
+        self.activate_skill(name)
+    }
+    fn end_round(&mut self) {
+        self.end_combat_round();
+    }
+    fn use_consumable(&mut self, position: usize) -> Option<String> {
+        self.queued_consumables.retain(|&queued| queued != position);
+        let name = self.inventory.get(position as i32)?.name();
+        self.consume(position).ok().map(|_| name)
+    }
+    fn apply_timed_modifier(&mut self, modifier: Modifier, rounds: u32, source: &str) {
+        self.add_timed_modifier(modifier, rounds, source);
     }
     fn set_life(&mut self, amount: i32) -> i32 {
-        self.current_life = max(amount, 0);
+        self.current_life = max(min(amount, self.max_life()), 0);
         self.current_life
     }
     fn life(&self) -> i32 {
@@ -264,13 +371,22 @@ impl Combatant for Character {
     }
 }
 
+impl Entity for Character {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
 impl Display for Character {
     fn name(&self) -> String {
         self.name.clone()
     }
+    fn noun_kind(&self) -> NounKind {
+        NounKind::Proper
+    }
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct CharacterAttributes {
     strength: i32,
     constitution: i32,
@@ -0,0 +1,293 @@
+use super::*;
+use std::fmt;
+
+/// How a modifier changes a stat. All flat modifiers are added to the
+/// base value first, then the sum of all percentages is applied to the result.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ModifierValue {
+    Flat(i32),
+    Percent(i32),
+}
+
+impl fmt::Display for ModifierValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ModifierValue::Flat(amount) => write!(f, "{:+}", amount),
+            ModifierValue::Percent(percent) => write!(f, "{:+}%", percent),
+        }
+    }
+}
+
+/// When a conditional modifier applies. Conditions are evaluated with
+/// stats that do not include conditional modifiers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Condition {
+    /// Current life is below the percentage of maximum life.
+    LifeBelowPercent(i32),
+    /// Current stamina is below the percentage of maximum stamina.
+    StaminaBelowPercent(i32),
+}
+
+impl fmt::Display for Condition {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Condition::LifeBelowPercent(percent) => write!(f, "while below {}% life", percent),
+            Condition::StaminaBelowPercent(percent) => {
+                write!(f, "while below {}% stamina", percent)
+            }
+        }
+    }
+}
+
+/// A change to an attribute or any other registered stat, eg. "+2 Strength
+/// while below 50% life".
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Modifier {
+    /// Name of the stat, eg. "Strength" or "CriticalChance".
+    pub stat: String,
+    pub value: ModifierValue,
+    #[serde(default)]
+    pub condition: Option<Condition>,
+}
+
+impl Modifier {
+    pub fn flat(stat: &str, amount: i32) -> Modifier {
+        Modifier {
+            stat: stat.to_owned(),
+            value: ModifierValue::Flat(amount),
+            condition: None,
+        }
+    }
+    pub fn percent(stat: &str, percent: i32) -> Modifier {
+        Modifier {
+            stat: stat.to_owned(),
+            value: ModifierValue::Percent(percent),
+            condition: None,
+        }
+    }
+    /// Makes the modifier apply only when the condition holds.
+    pub fn when(mut self, condition: Condition) -> Modifier {
+        self.condition = Some(condition);
+        self
+    }
+}
+
+/// A buff or a debuff that wears off after a number of combat rounds.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimedModifier {
+    pub modifier: Modifier,
+    /// Name of the item or skill that caused the modifier.
+    pub source: String,
+    pub rounds: u32,
+}
+
+/// A modifier that affects a stat and where it comes from.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contribution {
+    pub source: String,
+    pub value: ModifierValue,
+    pub condition: Option<Condition>,
+}
+
+impl fmt::Display for Contribution {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} from {}", self.value, self.source)?;
+        if let Some(condition) = self.condition {
+            write!(f, " {}", condition)?;
+        }
+        Ok(())
+    }
+}
+
+/// Final value of a stat and everything that contributed to it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatBreakdown {
+    pub stat: String,
+    /// Base value of a primary stat or the value of the formula of a derived stat.
+    pub base: i32,
+    /// Modifiers that currently apply.
+    pub contributions: Vec<Contribution>,
+    pub total: i32,
+}
+
+impl StatBreakdown {
+    fn new(stat: &str, base: i32, contributions: Vec<Contribution>) -> Self {
+        let mut flat = base;
+        let mut percent = 100;
+        for contribution in &contributions {
+            match contribution.value {
+                ModifierValue::Flat(amount) => flat += amount,
+                ModifierValue::Percent(amount) => percent += amount,
+            }
+        }
+        StatBreakdown {
+            stat: stat.to_owned(),
+            base,
+            contributions,
+            total: flat * max(percent, 0) / 100,
+        }
+    }
+}
+
+/// Eg. "Strength 9: base 3, +3 from Longsword, +50% from Berserk".
+impl fmt::Display for StatBreakdown {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}: base {}", self.stat, self.total, self.base)?;
+        for contribution in &self.contributions {
+            write!(f, ", {}", contribution)?;
+        }
+        Ok(())
+    }
+}
+
+/// Reasons why a consumable could not be used.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ConsumeError {
+    NothingThere,
+    NotConsumable,
+    /// Every action of the next combat round is already taken.
+    NoFreeAction,
+}
+
+/// Used for the Swiftness penalty of a heavy load.
+const LOAD_SOURCE: &str = "heavy load";
+
+impl Character {
+    /// Final value of the attribute and all modifiers that currently apply to it.
+    pub fn attribute_breakdown(&self, attribute: Attribute) -> StatBreakdown {
+        self.breakdown(attribute.name(), Scope::All)
+    }
+    /// Final value of the stat and all modifiers that currently apply to it.
+    /// None if the stat is not registered.
+    pub fn stat_breakdown(&self, stat: &str) -> Option<StatBreakdown> {
+        self.stats.get(stat)?;
+        Some(self.breakdown(stat, Scope::All))
+    }
+    /// Leaving out some of the modifiers keeps stats that affect them, eg. the
+    /// maximum life for conditions, from depending on themselves.
+    pub(super) fn breakdown(&self, stat: &str, scope: Scope) -> StatBreakdown {
+        let definition = self.stats.get(stat);
+        let base = match definition.and_then(|definition| definition.formula.as_ref()) {
+            Some(formula) => {
+                formula.evaluate(&|reference| self.stat_in(reference, scope).unwrap_or(0))
+            }
+            None => self.base_value(stat, definition.map_or(0, |definition| definition.default)),
+        };
+
+        let mut contributions = vec![];
+        {
+            let mut contribute = |source: &str, value, condition: Option<Condition>| {
+                if let Some(condition) = condition {
+                    if scope < Scope::All || !self.condition_holds(condition) {
+                        return;
+                    }
+                }
+                contributions.push(Contribution {
+                    source: source.to_owned(),
+                    value,
+                    condition,
+                });
+            };
+            for item in self.equipment.items.iter().filter_map(|(_, item)| item.as_ref()) {
+                for effect in item.effects() {
+                    if let Some((value, condition)) = effect.modifier_for(stat) {
+                        contribute(&item.name(), value, condition);
+                    }
+                }
+            }
+            for (source, effect) in self.set_bonuses() {
+                if let Some((value, condition)) = effect.modifier_for(stat) {
+                    contribute(&source, value, condition);
+                }
+            }
+            for timed in self.timed_modifiers.iter().filter(|timed| timed.modifier.stat == stat) {
+                contribute(&timed.source, timed.modifier.value, timed.modifier.condition);
+            }
+        }
+
+        // Heavy loads slow the character down
+        if stat == Attribute::Swiftness.name() && scope > Scope::Unloaded {
+            let penalty = self.encumbrance().swiftness_penalty();
+            if penalty > 0 {
+                contributions.push(Contribution {
+                    source: LOAD_SOURCE.to_owned(),
+                    value: ModifierValue::Flat(-penalty),
+                    condition: None,
+                });
+            }
+        }
+
+        StatBreakdown::new(stat, base, contributions)
+    }
+    fn condition_holds(&self, condition: Condition) -> bool {
+        match condition {
+            Condition::LifeBelowPercent(percent) => {
+                self.current_life * 100 < self.max_life_without_conditionals() * percent
+            }
+            Condition::StaminaBelowPercent(percent) => {
+                self.current_stamina * 100 < self.max_stamina_without_conditionals() * percent
+            }
+        }
+    }
+    /// Buffs and debuffs that are currently active.
+    pub fn timed_modifiers(&self) -> &[TimedModifier] {
+        self.timed_modifiers.as_slice()
+    }
+    /// Adds a buff or a debuff that lasts for the given number of combat rounds.
+    pub fn add_timed_modifier(&mut self, modifier: Modifier, rounds: u32, source: &str) {
+        if rounds == 0 {
+            return;
+        }
+        let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+        self.timed_modifiers.push(TimedModifier {
+            modifier,
+            source: source.to_owned(),
+            rounds,
+        });
+        self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+    }
+    /// Counts down one round of every timed modifier and removes expired ones.
+    pub fn tick_modifiers(&mut self) {
+        let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+        for timed in &mut self.timed_modifiers {
+            timed.rounds = timed.rounds.saturating_sub(1);
+        }
+        self.timed_modifiers.retain(|timed| timed.rounds > 0);
+        self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+    }
+    /// Uses the consumable at the inventory position in the next combat round
+    /// in place of an attack.
+    pub fn queue_consumable(&mut self, position: usize) -> Result<(), ConsumeError> {
+        match self.inventory.get(position as i32) {
+            None => return Err(ConsumeError::NothingThere),
+            Some(&Item::Consumable(_)) => {}
+            Some(_) => return Err(ConsumeError::NotConsumable),
+        }
+        if self.queued_actions() >= self.actions_per_round() {
+            return Err(ConsumeError::NoFreeAction);
+        }
+        self.queued_consumables.push(position);
+        Ok(())
+    }
+    /// Uses the consumable at the inventory position once and applies its
+    /// buffs. The consumable is removed when nothing is left of it.
+    pub fn consume(&mut self, position: usize) -> Result<(), ConsumeError> {
+        let (effects, name, used_up) = match self.inventory.get_mut(position as i32) {
+            None => return Err(ConsumeError::NothingThere),
+            Some(&mut Item::Consumable(ref mut consumable)) => {
+                let effects = consumable.effects().to_vec();
+                (effects, consumable.name(), !consumable.use_once())
+            }
+            Some(_) => return Err(ConsumeError::NotConsumable),
+        };
+        if used_up {
+            self.inventory.take(position as i32);
+        }
+        for effect in effects {
+            if let ItemEffect::Buff { modifier, rounds } = effect {
+                self.add_timed_modifier(modifier, rounds, &name);
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,234 @@
+use super::*;
+use display::noun::{capitalize, number_word};
+use skill::SkillLibrary;
+use std::collections::HashMap;
+
+/// Levels past this cannot be reached regardless of the curve.
+pub const MAX_LEVEL: u32 = 100;
+
+/// Defines how much total experience is required to reach each level.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum LevelCurve {
+    /// Every level requires the same amount of additional experience.
+    Linear { per_level: u32 },
+    /// The experience required for the next level grows by `factor` on every
+    /// level, starting from `base` for level 2.
+    Exponential { base: u32, factor: f32 },
+    /// Total experience required for levels 2, 3, 4... Levels past the end of
+    /// the table cannot be reached.
+    Table(Vec<u32>),
+}
+
+impl LevelCurve {
+    /// Total experience required to reach the level. None if the level cannot be reached.
+    pub fn threshold(&self, level: u32) -> Option<u32> {
+        if level <= 1 {
+            return Some(0);
+        }
+        match *self {
+            LevelCurve::Linear { per_level } => per_level.checked_mul(level - 1),
+            LevelCurve::Exponential { base, factor } => {
+                let mut total = 0f64;
+                let mut step = base as f64;
+                for _ in 1..level {
+                    total += step.round();
+                    step *= factor as f64;
+                }
+                if total > u32::MAX as f64 {
+                    None
+                } else {
+                    Some(total as u32)
+                }
+            }
+            LevelCurve::Table(ref thresholds) => thresholds.get(level as usize - 2).cloned(),
+        }
+    }
+    /// The level reached with the given amount of total experience.
+    pub fn level_for(&self, experience: u32) -> u32 {
+        let mut level = 1;
+        while level < MAX_LEVEL {
+            match self.threshold(level + 1) {
+                Some(threshold) if threshold <= experience => level += 1,
+                _ => break,
+            }
+        }
+        level
+    }
+}
+
+impl Default for LevelCurve {
+    fn default() -> Self {
+        LevelCurve::Exponential {
+            base: 100,
+            factor: 1.5,
+        }
+    }
+}
+
+/// Experience, level and unspent attribute points of a character.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Progression {
+    experience: u32,
+    level: u32,
+    unspent_points: u32,
+    curve: LevelCurve,
+    /// Attribute points awarded on every level-up.
+    points_per_level: u32,
+    /// Base attributes raised automatically on every level-up.
+    #[serde(default)]
+    growth: HashMap<Attribute, i32>,
+}
+
+impl Progression {
+    pub fn new(curve: LevelCurve, points_per_level: u32) -> Progression {
+        Progression {
+            experience: 0,
+            level: 1,
+            unspent_points: 0,
+            curve,
+            points_per_level,
+            growth: HashMap::new(),
+        }
+    }
+    pub fn experience(&self) -> u32 {
+        self.experience
+    }
+    pub fn level(&self) -> u32 {
+        self.level
+    }
+    pub fn unspent_points(&self) -> u32 {
+        self.unspent_points
+    }
+    pub fn curve(&self) -> &LevelCurve {
+        &self.curve
+    }
+    pub fn growth(&self) -> &HashMap<Attribute, i32> {
+        &self.growth
+    }
+    pub(super) fn set_growth(&mut self, growth: HashMap<Attribute, i32>) {
+        self.growth = growth;
+    }
+    /// Experience still required for the next level. None if the level cap has been reached.
+    pub fn experience_to_next_level(&self) -> Option<u32> {
+        if self.level >= MAX_LEVEL {
+            return None;
+        }
+        self.curve
+            .threshold(self.level + 1)
+            .map(|threshold| threshold.saturating_sub(self.experience))
+    }
+    /// Adds experience and returns an event for each level gained.
+    pub fn gain(&mut self, experience: u32) -> Vec<LevelUp> {
+        self.experience = self.experience.saturating_add(experience);
+        let new_level = self.curve.level_for(self.experience);
+        let mut events = vec![];
+        while self.level < new_level {
+            self.level += 1;
+            self.unspent_points += self.points_per_level;
+            events.push(LevelUp {
+                level: self.level,
+                attribute_points: self.points_per_level,
+                skills: vec![],
+            });
+        }
+        events
+    }
+    fn spend(&mut self, amount: u32) -> Result<(), SpendError> {
+        if amount == 0 {
+            return Err(SpendError::NothingToSpend);
+        }
+        if amount > self.unspent_points {
+            return Err(SpendError::NotEnoughPoints {
+                available: self.unspent_points,
+                requested: amount,
+            });
+        }
+        self.unspent_points -= amount;
+        Ok(())
+    }
+}
+
+impl Default for Progression {
+    fn default() -> Self {
+        Progression::new(LevelCurve::default(), DEFAULT_POINTS_PER_LEVEL)
+    }
+}
+
+pub const DEFAULT_POINTS_PER_LEVEL: u32 = 2;
+
+/// A level gained by a character.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct LevelUp {
+    /// The new level.
+    pub level: u32,
+    /// Attribute points awarded for the level.
+    pub attribute_points: u32,
+    /// Names of the skills learned on reaching the level.
+    #[serde(default)]
+    pub skills: Vec<String>,
+}
+
+impl LevelUp {
+    /// Eg. "Bob has reached level 3 and may spend two attribute points. Bob
+    /// has learned Power Strike."
+    pub fn narrate<D: Display>(&self, who: &D) -> String {
+        let name = capitalize(&who.definite_name());
+        let points = match self.attribute_points {
+            0 => String::new(),
+            1 => " and may spend an attribute point".to_owned(),
+            n => format!(" and may spend {} attribute points", number_word(n as usize)),
+        };
+        let skills = match self.skills.split_last() {
+            None => String::new(),
+            Some((last, [])) => format!(" {} has learned {}.", name, last),
+            Some((last, rest)) => {
+                format!(" {} has learned {} and {}.", name, rest.join(", "), last)
+            }
+        };
+        format!("{} has reached level {}{}.{}", name, self.level, points, skills)
+    }
+}
+
+/// Reasons why attribute points could not be spent.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SpendError {
+    NothingToSpend,
+    NotEnoughPoints { available: u32, requested: u32 },
+}
+
+impl Character {
+    pub fn level(&self) -> u32 {
+        self.progression.level()
+    }
+    pub fn experience(&self) -> u32 {
+        self.progression.experience()
+    }
+    pub fn progression(&self) -> &Progression {
+        &self.progression
+    }
+    /// Adds experience and returns an event for each level gained, for the game to narrate.
+    /// Attributes grow on each level as defined by the progression, and the skills of the
+    /// library that become available are learned, see `learn_for_level`.
+    pub fn gain_experience(&mut self, experience: u32, skills: &SkillLibrary) -> Vec<LevelUp> {
+        let mut level_ups = self.progression.gain(experience);
+        if !level_ups.is_empty() {
+            let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+            for level_up in &mut level_ups {
+                for (&attribute, &amount) in &self.progression.growth {
+                    *self.base_attributes.get_mut(attribute) += amount;
+                }
+                level_up.skills = self.learn_up_to(level_up.level, skills);
+            }
+            self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+        }
+        level_ups
+    }
+    /// Spends unspent attribute points to permanently raise a base attribute.
+    pub fn spend_points(&mut self, attribute: Attribute, amount: u32) -> Result<(), SpendError> {
+        self.progression.spend(amount)?;
+        let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+        *self.base_attributes.get_mut(attribute) += amount as i32;
+        self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+        Ok(())
+    }
+}
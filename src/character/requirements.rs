@@ -0,0 +1,40 @@
+use super::*;
+
+/// A requirement of an item that the character does not meet.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnmetRequirement {
+    pub attribute: Attribute,
+    pub required: i32,
+    pub actual: i32,
+}
+
+impl Character {
+    /// Requirements of the item that the character does not meet. Requirements
+    /// are checked against base attributes so that taking off other gear never
+    /// invalidates what is already worn.
+    pub fn unmet_requirements(&self, item: &Equipment) -> Vec<UnmetRequirement> {
+        item.requirements()
+            .iter()
+            .map(|requirement| UnmetRequirement {
+                attribute: requirement.attribute,
+                required: requirement.minimum,
+                actual: self.base_attributes.get(requirement.attribute),
+            })
+            .filter(|unmet| unmet.actual < unmet.required)
+            .collect()
+    }
+    /// Returns true if the character meets the requirements of the item and has slots for it.
+    pub fn can_wear(&self, item: &Equipment) -> bool {
+        self.equipment.fits(item) && self.unmet_requirements(item).is_empty()
+    }
+    /// Inventory positions of the equipment the character could currently wear.
+    pub fn wearable_items(&self) -> Vec<usize> {
+        (0..self.inventory.capacity())
+            .filter(|&pos| self.inventory.bounds(pos as i32).0 == pos)
+            .filter(|&pos| match self.inventory.get(pos as i32) {
+                Some(Item::Equipment(equipment)) => self.can_wear(equipment),
+                _ => false,
+            })
+            .collect()
+    }
+}
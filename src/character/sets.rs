@@ -0,0 +1,65 @@
+use super::*;
+
+impl EquipmentStore {
+    /// Names of the sets with worn pieces and the number of different pieces
+    /// worn of each. Identical items count as one piece.
+    pub fn worn_sets(&self) -> Vec<(&str, usize)> {
+        let mut sets: Vec<(&str, Vec<String>)> = vec![];
+        for item in self.items.iter().filter_map(|(_, item)| item.as_ref()) {
+            let set = match item.set() {
+                Some(set) => set,
+                None => continue,
+            };
+            let name = item.name();
+            match sets.iter_mut().find(|entry| entry.0 == set) {
+                Some(entry) => {
+                    if !entry.1.contains(&name) {
+                        entry.1.push(name);
+                    }
+                }
+                None => sets.push((set, vec![name])),
+            }
+        }
+        sets.into_iter().map(|(set, pieces)| (set, pieces.len())).collect()
+    }
+    /// Number of different pieces of the named set that are worn.
+    pub fn pieces_worn(&self, set_name: &str) -> usize {
+        self.worn_sets()
+            .into_iter()
+            .find(|&(set, _)| set == set_name)
+            .map_or(0, |(_, worn)| worn)
+    }
+    /// Progress of the set the item belongs to, eg. "Iahu's Regalia (2/4)".
+    /// None if the item is not a piece of a set in the library.
+    pub fn set_progress(&self, item: &Equipment, sets: &EquipmentSetLibrary) -> Option<String> {
+        let set = sets.get(item.set()?)?;
+        Some(set.progress(self.pieces_worn(&set.name)))
+    }
+}
+
+impl Character {
+    /// Effects of every set bonus tier reached, with the progress of the set as their source.
+    /// Sets missing from the character's set library grant nothing.
+    pub fn set_bonuses(&self) -> Vec<(String, ItemEffect)> {
+        let mut bonuses = vec![];
+        for (name, worn) in self.equipment.worn_sets() {
+            let set = match self.sets.get(name) {
+                Some(set) => set,
+                None => continue,
+            };
+            for effect in set.effects_for(worn) {
+                bonuses.push((set.progress(worn), effect));
+            }
+        }
+        bonuses
+    }
+    /// Set definitions used for the set bonuses of worn equipment.
+    pub fn sets(&self) -> &EquipmentSetLibrary {
+        &self.sets
+    }
+    /// The name of the item followed by the progress of its set, eg. "Crown,
+    /// Iahu's Regalia (2/4)".
+    pub fn display_item(&self, item: &Equipment) -> String {
+        item.display_with_set(&self.equipment, &self.sets)
+    }
+}
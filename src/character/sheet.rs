@@ -0,0 +1,219 @@
+use super::*;
+use std::fmt;
+
+/// Everything about a character at a glance, eg. for a UI or for debugging.
+/// Serializable, and renders as plain text with `Display`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct CharacterSheet {
+    pub name: String,
+    pub level: u32,
+    pub experience: u32,
+    /// None at the level cap.
+    pub experience_to_next_level: Option<u32>,
+    pub unspent_points: u32,
+    pub life: i32,
+    pub max_life: i32,
+    pub stamina: i32,
+    pub max_stamina: i32,
+    /// Base and effective value of each attribute with the sources of its modifiers.
+    pub attributes: Vec<StatBreakdown>,
+    pub equipment: Vec<SlotLine>,
+    pub inventory: Vec<InventoryLine>,
+    pub load: Load,
+    pub actions_per_round: usize,
+    /// Actions that can be taken in the next combat round, including the
+    /// skills that can be queued right now.
+    pub actions: Vec<String>,
+    pub skills: Vec<SkillLine>,
+    pub buffs: Vec<TimedModifier>,
+}
+
+/// A slot and what is worn in it.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlotLine {
+    pub slot: Slot,
+    pub item: Option<String>,
+    /// True if the item is stored in another slot, eg. the other hand of a
+    /// two-handed weapon.
+    pub extra: bool,
+    /// Eg. "Iahu's Regalia (2/4)".
+    pub set: Option<String>,
+}
+
+/// An item in the inventory, and the contents of containers.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InventoryLine {
+    pub position: usize,
+    /// Name with the quantity, eg. "three arrows", or with the progress of
+    /// the set for pieces of a set.
+    pub name: String,
+    pub weight: u32,
+    pub contents: Vec<InventoryLine>,
+}
+
+/// A known skill and the rounds left before it can be used again.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SkillLine {
+    pub name: String,
+    pub stamina_cost: i32,
+    pub cooldown: u32,
+}
+
+impl Character {
+    fn inventory_lines(&self, inventory: &Inventory) -> Vec<InventoryLine> {
+        inventory
+            .iter()
+            .map(|entry| InventoryLine {
+                position: entry.position,
+                name: match *entry.item {
+                    Item::Equipment(ref equipment) => self.display_item(equipment),
+                    ref item => item.quantified_name(),
+                },
+                weight: entry.item.weight(),
+                contents: match *entry.item {
+                    Item::Container(ref container) => self.inventory_lines(container.contents()),
+                    _ => vec![],
+                },
+            })
+            .collect()
+    }
+    /// Gathers the current state of the character.
+    pub fn sheet(&self) -> CharacterSheet {
+        let equipment = (0..self.equipment.items.len())
+            .map(|idx| {
+                let item = self.equipment.owner_of(idx).and_then(|owner| self.equipment.at(owner));
+                SlotLine {
+                    slot: self.equipment.items[idx].0,
+                    item: item.map(|item| item.name()),
+                    extra: self.equipment.at(idx).is_none() && item.is_some(),
+                    set: item.and_then(|item| self.equipment.set_progress(item, &self.sets)),
+                }
+            })
+            .collect();
+        let mut actions: Vec<String> = self.actions().iter().map(String::from).collect();
+        actions.extend(
+            self.skills
+                .iter()
+                .filter(|skill| self.can_queue_skill(&skill.name).is_ok())
+                .map(|skill| String::from(&Action::Skill(skill.name.clone()))),
+        );
+        CharacterSheet {
+            name: self.name.clone(),
+            level: self.level(),
+            experience: self.experience(),
+            experience_to_next_level: self.progression.experience_to_next_level(),
+            unspent_points: self.progression.unspent_points(),
+            life: self.life(),
+            max_life: self.max_life(),
+            stamina: self.stamina(),
+            max_stamina: self.max_stamina(),
+            attributes: Attribute::all()
+                .iter()
+                .map(|&attribute| self.attribute_breakdown(attribute))
+                .collect(),
+            equipment,
+            inventory: self.inventory_lines(&self.inventory),
+            load: self.load(),
+            actions_per_round: self.actions_per_round(),
+            actions,
+            skills: self
+                .skills
+                .iter()
+                .map(|skill| SkillLine {
+                    name: skill.name.clone(),
+                    stamina_cost: skill.stamina_cost,
+                    cooldown: self.cooldown(&skill.name),
+                })
+                .collect(),
+            buffs: self.timed_modifiers.clone(),
+        }
+    }
+}
+
+fn write_inventory(f: &mut fmt::Formatter, lines: &[InventoryLine], depth: usize) -> fmt::Result {
+    for line in lines {
+        writeln!(
+            f,
+            "{:indent$}  [{}] {} ({} wt)",
+            "",
+            line.position,
+            line.name,
+            line.weight,
+            indent = depth * 2
+        )?;
+        write_inventory(f, &line.contents, depth + 1)?;
+    }
+    Ok(())
+}
+
+impl fmt::Display for CharacterSheet {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "{}, level {}", self.name, self.level)?;
+        match self.experience_to_next_level {
+            Some(missing) => {
+                writeln!(f, "Experience: {} ({} to next level)", self.experience, missing)?
+            }
+            None => writeln!(f, "Experience: {}", self.experience)?,
+        }
+        if self.unspent_points > 0 {
+            writeln!(f, "Unspent attribute points: {}", self.unspent_points)?;
+        }
+        writeln!(f, "Life: {}/{}", self.life, self.max_life)?;
+        writeln!(f, "Stamina: {}/{}", self.stamina, self.max_stamina)?;
+
+        writeln!(f, "Attributes:")?;
+        for attribute in &self.attributes {
+            writeln!(f, "  {}", attribute)?;
+        }
+
+        writeln!(f, "Equipment:")?;
+        for line in &self.equipment {
+            let slot: &str = (&line.slot).into();
+            match line.item {
+                None => writeln!(f, "  {}: -", slot)?,
+                Some(ref item) => {
+                    let extra = if line.extra { " (also)" } else { "" };
+                    match line.set {
+                        Some(ref set) => writeln!(f, "  {}: {}{}, {}", slot, item, extra, set)?,
+                        None => writeln!(f, "  {}: {}{}", slot, item, extra)?,
+                    }
+                }
+            }
+        }
+
+        writeln!(f, "Inventory:")?;
+        if self.inventory.is_empty() {
+            writeln!(f, "  -")?;
+        }
+        write_inventory(f, &self.inventory, 0)?;
+        writeln!(
+            f,
+            "Load: {}/{} ({:?})",
+            self.load.weight, self.load.carry_limit, self.load.encumbrance
+        )?;
+
+        writeln!(f, "Actions per round: {}", self.actions_per_round)?;
+        writeln!(f, "Actions: {}", self.actions.join(", "))?;
+        if !self.skills.is_empty() {
+            writeln!(f, "Skills:")?;
+            for skill in &self.skills {
+                match skill.cooldown {
+                    0 => writeln!(f, "  {} ({} stamina)", skill.name, skill.stamina_cost)?,
+                    rounds => writeln!(
+                        f,
+                        "  {} ({} stamina, {} rounds)",
+                        skill.name, skill.stamina_cost, rounds
+                    )?,
+                }
+            }
+        }
+        for buff in &self.buffs {
+            writeln!(
+                f,
+                "Effect: {} {} from {} ({} rounds)",
+                buff.modifier.value, buff.modifier.stat, buff.source, buff.rounds
+            )?;
+        }
+        Ok(())
+    }
+}
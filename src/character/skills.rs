@@ -0,0 +1,146 @@
+use super::*;
+use skill::*;
+
+impl Character {
+    /// Skills the character has learned.
+    pub fn skills(&self) -> &[Skill] {
+        self.skills.as_slice()
+    }
+    pub fn knows(&self, name: &str) -> bool {
+        self.skill(name).is_some()
+    }
+    fn skill(&self, name: &str) -> Option<&Skill> {
+        self.skills.iter().find(|skill| skill.name == name)
+    }
+    /// Learns the skill if the character meets its prerequisites.
+    pub fn learn(&mut self, skill: &Skill) -> Result<(), SkillError> {
+        if self.knows(&skill.name) {
+            return Err(SkillError::AlreadyKnown);
+        }
+        let unmet: Vec<UnmetRequirement> = skill
+            .prerequisites
+            .iter()
+            .map(|requirement| UnmetRequirement {
+                attribute: requirement.attribute,
+                required: requirement.minimum,
+                actual: self.base_attributes.get(requirement.attribute),
+            })
+            .filter(|unmet| unmet.actual < unmet.required)
+            .collect();
+        if !unmet.is_empty() {
+            return Err(SkillError::UnmetPrerequisites(unmet));
+        }
+        self.skills.push(skill.clone());
+        Ok(())
+    }
+    /// Learns every skill of the library that is available at the character's
+    /// level and whose prerequisites are met. Returns the names of the learned skills.
+    /// `gain_experience` does this on every level up, call this eg. after the
+    /// prerequisites have been met by spending attribute points.
+    pub fn learn_for_level(&mut self, library: &SkillLibrary) -> Vec<String> {
+        let level = self.level();
+        self.learn_up_to(level, library)
+    }
+    pub(super) fn learn_up_to(&mut self, level: u32, library: &SkillLibrary) -> Vec<String> {
+        library
+            .skills()
+            .iter()
+            .filter(|skill| skill.level.is_some_and(|required| required <= level))
+            .filter(|skill| self.learn(skill).is_ok())
+            .map(|skill| skill.name.clone())
+            .collect()
+    }
+    /// Learns the skills taught by the item, eg. a tome. Skills that are
+    /// already known are skipped. Returns the names of the learned skills.
+    pub fn learn_from(
+        &mut self,
+        item: &Item,
+        library: &SkillLibrary,
+    ) -> Result<Vec<String>, SkillError> {
+        let mut skills = vec![];
+        for name in item.teaches() {
+            match library.get(name) {
+                Some(skill) => skills.push(skill),
+                None => return Err(SkillError::Unknown(name.clone())),
+            }
+        }
+        let mut learned = vec![];
+        for skill in skills {
+            match self.learn(skill) {
+                Ok(()) => learned.push(skill.name.clone()),
+                Err(SkillError::AlreadyKnown) => {}
+                Err(error) => return Err(error),
+            }
+        }
+        Ok(learned)
+    }
+    /// Rounds left before the skill can be used again.
+    pub fn cooldown(&self, name: &str) -> u32 {
+        self.cooldowns.get(name).cloned().unwrap_or(0)
+    }
+    /// Uses the skill in the next combat round in place of an attack.
+    pub fn queue_skill(&mut self, name: &str) -> Result<(), SkillError> {
+        self.can_queue_skill(name)?;
+        self.queued_skills.push(name.to_owned());
+        Ok(())
+    }
+    /// Checks whether `queue_skill` would accept the skill, without queuing it.
+    pub fn can_queue_skill(&self, name: &str) -> Result<(), SkillError> {
+        let cost = match self.skill(name) {
+            Some(skill) => self.stamina_cost(skill.stamina_cost),
+            None => return Err(SkillError::NotKnown),
+        };
+        match self.cooldown(name) {
+            0 => {}
+            rounds => return Err(SkillError::OnCooldown(rounds)),
+        }
+        if self.queued_skills.iter().any(|queued| queued == name) {
+            return Err(SkillError::AlreadyQueued);
+        }
+        if self.queued_actions() >= self.actions_per_round() {
+            return Err(SkillError::NoFreeAction);
+        }
+        let reserved: i32 = self
+            .queued_skills
+            .iter()
+            .filter_map(|queued| self.skill(queued))
+            .map(|skill| self.stamina_cost(skill.stamina_cost))
+            .sum();
+        if reserved + cost > self.stamina() {
+            return Err(SkillError::NotEnoughStamina {
+                required: cost,
+                available: self.stamina() - reserved,
+            });
+        }
+        Ok(())
+    }
+    pub fn queued_skills(&self) -> &[String] {
+        self.queued_skills.as_slice()
+    }
+    /// Pays for the skill and starts its cooldown. None if the skill can't be used.
+    pub(super) fn activate_skill(&mut self, name: &str) -> Option<Vec<SkillEffect>> {
+        self.queued_skills.retain(|queued| queued != name);
+        let skill = self.skill(name)?.clone();
+        if self.cooldown(name) > 0 || !self.spend_stamina(skill.stamina_cost) {
+            return None;
+        }
+        // The cooldown ticks once at the end of this round
+        self.cooldowns.insert(skill.name.clone(), skill.cooldown + 1);
+        Some(skill.effects)
+    }
+    /// Number of actions reserved for skills and consumables in the next combat round.
+    pub(super) fn queued_actions(&self) -> usize {
+        self.queued_skills.len() + self.queued_consumables.len()
+    }
+    /// Ticks cooldowns and timed modifiers, and forgets skills and consumables
+    /// that were queued but not used.
+    pub(super) fn end_combat_round(&mut self) {
+        for rounds in self.cooldowns.values_mut() {
+            *rounds = rounds.saturating_sub(1);
+        }
+        self.cooldowns.retain(|_, &mut rounds| rounds > 0);
+        self.queued_skills.clear();
+        self.queued_consumables.clear();
+        self.tick_modifiers();
+    }
+}
@@ -0,0 +1,50 @@
+use super::*;
+use stat::StatRegistry;
+
+/// How many of the modifiers are taken into account when evaluating stats.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub(super) enum Scope {
+    /// Leaves out the penalty of a heavy load, which itself depends on the carry limit.
+    Unloaded,
+    /// Leaves out conditional modifiers, which can't depend on themselves.
+    Unconditional,
+    All,
+}
+
+impl Character {
+    /// Value of any stat of the character's stat registry. Attributes include
+    /// all their modifiers, other primary stats start from the character's own
+    /// base value or the default of the stat. None if the stat is not registered.
+    pub fn stat(&self, name: &str) -> Option<i32> {
+        self.stat_in(name, Scope::All)
+    }
+    pub(super) fn stat_in(&self, name: &str, scope: Scope) -> Option<i32> {
+        self.stats.get(name)?;
+        Some(self.breakdown(name, scope).total)
+    }
+    /// Base value of a primary stat before modifiers.
+    pub(super) fn base_value(&self, name: &str, default: i32) -> i32 {
+        match Attribute::from_name(name) {
+            Some(attribute) => self.base_attributes.get(attribute),
+            None if name == DerivedStat::WeaponDamage.name() => self.best_weapon().damage(),
+            None => self.base_stats.get(name).cloned().unwrap_or(default),
+        }
+    }
+    /// Stats used by the character, see `CharacterBuilder::stats`.
+    pub fn stats(&self) -> &StatRegistry {
+        &self.stats
+    }
+    /// Sets the base value of a primary stat that is not an attribute, eg. "Luck".
+    pub fn set_base_stat(&mut self, name: &str, value: i32) {
+        match Attribute::from_name(name) {
+            Some(attribute) => {
+                let (old_max_life, old_max_stamina) = (self.max_life(), self.max_stamina());
+                self.base_attributes.set(attribute, value);
+                self.adapt_to_new_maximums(old_max_life, old_max_stamina);
+            }
+            None => {
+                self.base_stats.insert(name.to_owned(), value);
+            }
+        }
+    }
+}
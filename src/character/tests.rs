@@ -0,0 +1,698 @@
+use character::*;
+use combat::*;
+use item::*;
+use skill::*;
+
+#[test]
+fn experience_awards_levels_and_points() {
+    // Arrange
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default())
+        .named("Bob")
+        .progression(LevelCurve::Linear { per_level: 100 }, 2)
+        .build();
+
+    // Act
+    let level_ups = character.gain_experience(250, &SkillLibrary::default());
+
+    // Assert
+    assert_eq!(character.level(), 3);
+    assert_eq!(character.progression().unspent_points(), 4);
+    assert_eq!(character.progression().experience_to_next_level(), Some(50));
+    assert_eq!(level_ups.len(), 2);
+    assert_eq!(
+        level_ups[1].narrate(&character),
+        "Bob has reached level 3 and may spend two attribute points."
+    );
+}
+
+#[test]
+fn level_curves_define_thresholds() {
+    let exponential = LevelCurve::Exponential { base: 100, factor: 2. };
+    let table = LevelCurve::Table(vec![10, 30]);
+
+    assert_eq!(exponential.threshold(1), Some(0));
+    assert_eq!(exponential.threshold(2), Some(100));
+    assert_eq!(exponential.threshold(4), Some(700));
+    assert_eq!(exponential.level_for(699), 3);
+    assert_eq!(table.level_for(1000), 3);
+    assert_eq!(table.threshold(4), None);
+}
+
+#[test]
+fn attribute_points_are_validated() {
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default())
+        .progression(LevelCurve::Linear { per_level: 10 }, 1)
+        .build();
+    character.gain_experience(10, &SkillLibrary::default());
+
+    assert_eq!(
+        character.spend_points(Attribute::Strength, 2),
+        Err(SpendError::NotEnoughPoints { available: 1, requested: 2 })
+    );
+    assert_eq!(character.spend_points(Attribute::Strength, 0), Err(SpendError::NothingToSpend));
+    assert_eq!(character.spend_points(Attribute::Strength, 1), Ok(()));
+    assert_eq!(character.attribute(&Attribute::Strength), 2);
+    assert_eq!(character.progression().unspent_points(), 0);
+}
+
+#[test]
+fn max_life_tracks_constitution_from_items() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 5);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    let armor = equipment(
+        "Chain Mail",
+        2,
+        Slot::Torso,
+        vec![ItemEffect::AttributeModifier(Attribute::Constitution, 3)],
+    ).build();
+
+    // Act & assert
+    assert_eq!(character.max_life(), 5);
+    assert_eq!(character.life(), 5);
+
+    character.equip(armor).unwrap();
+    assert_eq!(character.max_life(), 8);
+    assert_eq!(character.life(), 8);
+
+    character.set_life(7);
+    let torso = character.slots().iter().position(|&&slot| slot == Slot::Torso).unwrap();
+    character.unequip(torso);
+    assert_eq!(character.max_life(), 5);
+    assert_eq!(character.life(), 5);
+}
+
+#[test]
+fn healing_respects_max_life() {
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 6);
+    attributes.set(Attribute::Endurance, 4);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    character.set_life(2);
+
+    assert_eq!(character.heal(3), 3);
+    assert_eq!(character.heal(10), 1);
+    assert_eq!(character.life(), 6);
+    assert_eq!(character.set_life(100), 6);
+
+    assert!(character.spend_stamina(3));
+    assert!(!character.spend_stamina(3));
+    assert_eq!(character.restore_stamina(5), 3);
+    assert_eq!(character.stamina(), 4);
+}
+
+#[test]
+fn swiftness_derives_actions_per_round() {
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Swiftness, 4);
+    let fast = CharacterBuilder::new(8, attributes).build();
+
+    assert_eq!(Character::default().actions_per_round(), 1);
+    assert_eq!(fast.actions_per_round(), 2);
+}
+
+#[test]
+fn equipping_checks_attribute_requirements() {
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    let axe = equipment("Heavy Axe", 2, Slot::Hand, vec![])
+        .requires(Attribute::Strength, 5)
+        .requires(Attribute::Swiftness, 1)
+        .build();
+
+    match character.equip(axe.clone()) {
+        Err(EquipError::UnmetRequirements { item, unmet }) => {
+            assert_eq!(item.name(), axe.name());
+            assert_eq!(
+                unmet,
+                vec![UnmetRequirement {
+                    attribute: Attribute::Strength,
+                    required: 5,
+                    actual: 3,
+                }]
+            );
+        }
+        _ => panic!("equipped an axe that is too heavy"),
+    }
+    assert!(character.equipment().by_slot(&Slot::Hand).is_empty());
+
+    character.gain_experience(1000, &SkillLibrary::default());
+    character.spend_points(Attribute::Strength, 2).unwrap();
+    assert!(character.equip(axe).is_ok());
+}
+
+#[test]
+fn wearable_items_lists_inventory_positions() {
+    let character_attributes = CharacterAttributes::default();
+    let mut character = CharacterBuilder::new(8, character_attributes).build();
+    let dagger = equipment("Dagger", 1, Slot::Hand, vec![]).build();
+    let axe = equipment("Heavy Axe", 2, Slot::Hand, vec![])
+        .requires(Attribute::Strength, 5)
+        .build();
+    let potion = consumable("Potion", 1, vec![]).build();
+    character.inventory.put(axe.into());
+    character.inventory.put(potion.into());
+    character.inventory.put(dagger.into());
+
+    assert_eq!(character.wearable_items(), vec![3]);
+}
+
+fn saved_character() -> Character {
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default())
+        .named("Bob")
+        .build();
+    let robe = equipment(
+        "Robe",
+        2,
+        Slot::Torso,
+        vec![ItemEffect::AttributeModifier(Attribute::Constitution, 3)],
+    ).also_occupies(Slot::Feet)
+        .build();
+    let sword = equipment("Sword", 2, Slot::Hand, vec![]).damage(2).verb("slash").build();
+    let potion = consumable("Potion", 1, vec![]).remaining_uses(1, 3).build();
+    character.equip(robe).unwrap();
+    character.equip(sword.clone()).unwrap();
+    // Leave gaps in the inventory to check that positions are kept
+    character.inventory.put_at(sword.into(), 1);
+    character.inventory.put_at(potion.into(), 5);
+    character.gain_experience(120, &SkillLibrary::default());
+    character.set_life(2);
+    character
+}
+
+#[test]
+fn character_round_trips_through_yaml() {
+    let character = saved_character();
+
+    let yaml = ::serde_yaml::to_string(&character).unwrap();
+    let loaded: Character = ::serde_yaml::from_str(&yaml).unwrap();
+
+    assert_eq!(loaded, character);
+    assert_eq!(loaded.inventory.bounds(2), (1, 2));
+    assert!(!loaded.inventory.is_reserved(3));
+    assert!(loaded.inventory.is_reserved(5));
+}
+
+#[test]
+fn character_round_trips_through_json() {
+    let character = saved_character();
+
+    let json = ::serde_json::to_string(&character).unwrap();
+    let loaded: Character = ::serde_json::from_str(&json).unwrap();
+
+    assert_eq!(loaded, character);
+    assert_eq!(loaded.life(), 2);
+    assert_eq!(loaded.max_life(), 4);
+}
+
+#[test]
+fn equipping_from_inventory_stores_displaced_items() {
+    let mut character = CharacterBuilder::new(4, CharacterAttributes::default()).build();
+    let sword = equipment("Sword", 2, Slot::Hand, vec![]).build();
+    let shield = equipment("Shield", 2, Slot::Hand, vec![]).build();
+    let greatsword = equipment("Greatsword", 3, Slot::Hand, vec![]).two_handed().build();
+    character.equip(sword.clone()).unwrap();
+    character.equip(shield.clone()).unwrap();
+    character.inventory.put_at(greatsword.clone().into(), 1);
+
+    // The space freed by the greatsword is used for the sword and the shield
+    let positions = character.equip_from_inventory(2).unwrap();
+
+    assert_eq!(positions, vec![0, 2]);
+    assert_eq!(character.inventory.get(0).unwrap().name(), sword.name());
+    assert_eq!(character.inventory.get(2).unwrap().name(), shield.name());
+    assert_eq!(character.equipment().by_slot(&Slot::Hand)[0].name(), greatsword.name());
+}
+
+#[test]
+fn failed_transfers_change_nothing() {
+    let mut character = CharacterBuilder::new(4, CharacterAttributes::default()).build();
+    let sword = equipment("Sword", 2, Slot::Hand, vec![]).build();
+    let shield = equipment("Shield", 3, Slot::Hand, vec![]).build();
+    let greatsword = equipment("Greatsword", 1, Slot::Hand, vec![]).two_handed().build();
+    let potion = consumable("Potion", 1, vec![]).build();
+    character.equip(sword).unwrap();
+    character.equip(shield).unwrap();
+    character.inventory.put_at(greatsword.into(), 0);
+    character.inventory.put_at(potion.into(), 2);
+    let before = character.clone();
+
+    // Sword and shield do not both fit
+    match character.equip_from_inventory(0) {
+        Err(TransferError::InventoryFull) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(character, before);
+
+    match character.equip_from_inventory(2) {
+        Err(TransferError::NotEquipment) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    match character.equip_from_inventory(3) {
+        Err(TransferError::NothingThere) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // The shield does not fit
+    let shield_slot = (0..5).find(|&idx| character.equipment().at(idx).map(|item| item.size()) == Some(3));
+    match character.unequip_to_inventory(shield_slot.unwrap()) {
+        Err(TransferError::InventoryFull) => {}
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(character, before);
+}
+
+#[test]
+fn unequipping_to_inventory() {
+    let mut character = CharacterBuilder::new(4, CharacterAttributes::default()).build();
+    let robe = equipment("Robe", 2, Slot::Torso, vec![]).also_occupies(Slot::Feet).build();
+    character.equip(robe.clone()).unwrap();
+    let feet = character.slots().iter().position(|&&slot| slot == Slot::Feet).unwrap();
+
+    assert_eq!(character.unequip_to_inventory(feet).unwrap(), 0);
+    assert_eq!(character.inventory.get(1).unwrap().name(), robe.name());
+    assert!(character.equipment().by_slot(&Slot::Torso).is_empty());
+}
+
+#[test]
+fn heavy_loads_slow_the_character_down() {
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 2);
+    attributes.set(Attribute::Swiftness, 7);
+    attributes.set(Attribute::Endurance, 10);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    let armor = equipment("Plate Armor", 1, Slot::Torso, vec![]).weight(15).build();
+    let rocks = consumable("rock", 1, vec![]).weight(2).stack_limit(10).count(5).build();
+    assert_eq!(character.carry_limit(), 20);
+    assert_eq!(character.actions_per_round(), 3);
+
+    character.equip(armor).unwrap();
+    assert_eq!(character.load().encumbrance, Encumbrance::Unburdened);
+    assert_eq!(character.load().remaining(), 5);
+
+    // Warn before picking up the rocks
+    let with_rocks = character.load_with(&rocks.clone().into());
+    assert_eq!(with_rocks.weight, 25);
+    assert_eq!(with_rocks.encumbrance, Encumbrance::Burdened);
+    character.inventory.put(rocks.into());
+    assert_eq!(character.attribute(&Attribute::Swiftness), 5);
+    assert_eq!(character.actions_per_round(), 2);
+    assert_eq!(character.stamina_cost(3), 5);
+
+    let anvil = consumable("anvil", 1, vec![]).weight(10).build();
+    character.inventory.put(anvil.into());
+    assert_eq!(character.encumbrance(), Encumbrance::Overburdened);
+    assert_eq!(character.attribute(&Attribute::Swiftness), 5);
+    assert_eq!(character.actions_per_round(), 1);
+    assert!(character.spend_stamina(3));
+    assert_eq!(character.stamina(), 4);
+}
+
+fn skill_library() -> SkillLibrary {
+    SkillLibrary::from_yaml(include_str!("../../data/txt/skills.yml")).unwrap()
+}
+
+#[test]
+fn skills_are_learned_on_level_up() {
+    // Arrange
+    let library = skill_library();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    let mut character = CharacterBuilder::new(8, attributes)
+        .named("Bob")
+        .progression(LevelCurve::Linear { per_level: 10 }, 1)
+        .build();
+
+    // Act
+    let at_first_level = character.learn_for_level(&library);
+    let level_ups = character.gain_experience(20, &library);
+
+    // Assert
+    assert!(at_first_level.is_empty());
+    // Second Wind requires more Endurance, Shield Bash must be taught
+    assert_eq!(level_ups[0].skills, vec!["Power Strike".to_owned()]);
+    assert!(level_ups[1].skills.is_empty());
+    assert_eq!(
+        level_ups[0].narrate(&character),
+        "Bob has reached level 2 and may spend an attribute point. Bob has learned Power Strike."
+    );
+    assert!(character.knows("Power Strike"));
+    assert!(!character.knows("Second Wind"));
+    assert_eq!(character.learn_for_level(&library), Vec::<String>::new());
+}
+
+#[test]
+fn skills_are_learned_from_items() {
+    let library = skill_library();
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default()).build();
+    let manual: Item = consumable("Bashing Manual", 1, vec![])
+        .teaches("Shield Bash")
+        .build()
+        .into();
+    let tome: Item = consumable("Tome of Vigor", 1, vec![]).teaches("Second Wind").build().into();
+    let forgery: Item = consumable("Forged Tome", 1, vec![]).teaches("Fireball").build().into();
+
+    assert_eq!(character.learn_from(&manual, &library), Ok(vec!["Shield Bash".to_owned()]));
+    assert_eq!(character.learn_from(&manual, &library), Ok(vec![]));
+    assert_eq!(
+        character.learn_from(&tome, &library),
+        Err(SkillError::UnmetPrerequisites(vec![UnmetRequirement {
+            attribute: Attribute::Endurance,
+            required: 4,
+            actual: 1,
+        }]))
+    );
+    assert_eq!(
+        character.learn_from(&forgery, &library),
+        Err(SkillError::Unknown("Fireball".to_owned()))
+    );
+    assert_eq!(character.skills().len(), 1);
+}
+
+#[test]
+fn queued_skills_are_validated() {
+    // Arrange
+    let library = skill_library();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Endurance, 4);
+    let mut character = CharacterBuilder::new(8, attributes)
+        .skill(library.get("Power Strike").unwrap())
+        .skill(library.get("Shield Bash").unwrap())
+        .build();
+
+    // Act & Assert
+    assert_eq!(character.queue_skill("Second Wind"), Err(SkillError::NotKnown));
+    assert_eq!(character.queue_skill("Power Strike"), Ok(()));
+    assert_eq!(character.queue_skill("Power Strike"), Err(SkillError::AlreadyQueued));
+    assert_eq!(character.queue_skill("Shield Bash"), Err(SkillError::NoFreeAction));
+    assert_eq!(
+        character.action_buffer().actions(),
+        &[Action::Skill("Power Strike".to_owned())]
+    );
+    assert_eq!(character.use_skill("Power Strike").map(|effects| effects.len()), Some(1));
+    assert_eq!(character.stamina(), 1);
+    assert_eq!(character.cooldown("Power Strike"), 3);
+    character.end_round();
+    assert_eq!(character.queue_skill("Power Strike"), Err(SkillError::OnCooldown(2)));
+    assert_eq!(
+        character.queue_skill("Shield Bash"),
+        Err(SkillError::NotEnoughStamina { required: 2, available: 1 })
+    );
+    character.end_round();
+    character.end_round();
+    assert_eq!(character.cooldown("Power Strike"), 0);
+}
+
+fn class_library() -> ClassLibrary {
+    ClassLibrary::from_yaml(include_str!("../../data/txt/classes.yml")).unwrap()
+}
+
+#[test]
+fn characters_are_created_from_classes() {
+    // Arrange
+    let classes = class_library();
+    let skills = skill_library();
+
+    // Act
+    let builder = CharacterBuilder::from_class("warrior", &classes, &skills).unwrap();
+    let other = builder.build();
+    let bob = builder.named("Bob").build();
+
+    // Assert
+    assert_eq!(bob.name(), "Bob");
+    assert_eq!(bob.attribute(&Attribute::Constitution), 7);
+    assert_eq!(bob.life(), 7);
+    assert_eq!(bob.damage(), 6);
+    assert!(bob.knows("Power Strike"));
+    assert_eq!(bob.inventory.iter().map(|entry| entry.item.quantity()).sum::<usize>(), 2);
+    // Every character gets their own copies of the starting items
+    let sword = bob.equipment().first_in_slot(&Slot::Hand).unwrap();
+    assert!(other.equipment().find_by_id(sword.id()).is_none());
+    assert_ne!(
+        bob.inventory.iter().next().unwrap().item.id(),
+        other.inventory.iter().next().unwrap().item.id()
+    );
+}
+
+#[test]
+fn classes_define_slots_and_growth() {
+    let classes = class_library();
+    let mut rogue = CharacterBuilder::from_class("rogue", &classes, &SkillLibrary::default())
+        .unwrap()
+        .progression(LevelCurve::Linear { per_level: 10 }, 0)
+        .build();
+
+    rogue.gain_experience(20, &SkillLibrary::default());
+
+    assert_eq!(rogue.slots().len(), 6);
+    assert_eq!(rogue.equipment().by_slot(&Slot::Hand).len(), 2);
+    assert_eq!(rogue.attribute(&Attribute::Swiftness), 6);
+    assert_eq!(rogue.attribute(&Attribute::Strength), 2);
+}
+
+#[test]
+fn broken_classes_are_reported() {
+    let classes = class_library();
+    let mut greedy = classes.get("warrior").unwrap().clone();
+    greedy.id = "greedy".to_owned();
+    greedy.inventory_space = 0;
+    let mut clumsy = classes.get("rogue").unwrap().clone();
+    clumsy.id = "clumsy".to_owned();
+    clumsy.slots = Some(vec![Slot::Hand]);
+    let classes = ClassLibrary::new(vec![greedy, clumsy]);
+    let skills = skill_library();
+
+    let error = |id| CharacterBuilder::from_class(id, &classes, &skills).err();
+    assert_eq!(error("greedy"), Some(ClassError::InventoryFull("Healing Potion".to_owned())));
+    assert_eq!(error("clumsy"), Some(ClassError::CannotEquip("Dagger".to_owned())));
+    assert_eq!(error("bard"), Some(ClassError::UnknownClass("bard".to_owned())));
+    assert_eq!(
+        CharacterBuilder::from_class("warrior", &class_library(), &SkillLibrary::default()).err(),
+        Some(ClassError::UnknownSkill("Power Strike".to_owned()))
+    );
+}
+
+#[test]
+fn percentages_apply_after_flat_modifiers() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    let sword = equipment(
+        "Longsword",
+        4,
+        Slot::Hand,
+        vec![
+            ItemEffect::AttributeModifier(Attribute::Strength, 3),
+            ItemEffect::Modifier(Modifier::percent("Strength", 50)),
+        ],
+    ).build();
+
+    // Act
+    character.equip(sword).unwrap();
+    let breakdown = character.attribute_breakdown(Attribute::Strength);
+
+    // Assert
+    assert_eq!(breakdown.total, 9);
+    assert_eq!(character.attribute(&Attribute::Strength), 9);
+    assert_eq!(
+        breakdown.to_string(),
+        "Strength 9: base 3, +3 from Longsword, +50% from Longsword"
+    );
+}
+
+#[test]
+fn conditional_modifiers_apply_when_the_condition_holds() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 10);
+    let mut character = CharacterBuilder::new(8, attributes).build();
+    let below_half = Condition::LifeBelowPercent(50);
+    let amulet = equipment(
+        "Amulet of Despair",
+        1,
+        Slot::Head,
+        vec![
+            ItemEffect::Modifier(Modifier::flat("Strength", 2).when(below_half)),
+            // Conditions are evaluated without conditional modifiers
+            ItemEffect::Modifier(Modifier::percent("Constitution", 100).when(below_half)),
+        ],
+    ).build();
+    character.equip(amulet).unwrap();
+
+    // Act & Assert
+    assert_eq!(character.attribute(&Attribute::Strength), 1);
+    character.set_life(4);
+    assert_eq!(character.attribute(&Attribute::Strength), 3);
+    assert_eq!(character.max_life(), 20);
+    assert_eq!(
+        character.attribute_breakdown(Attribute::Strength).to_string(),
+        "Strength 3: base 1, +2 from Amulet of Despair while below 50% life"
+    );
+}
+
+#[test]
+fn consumables_give_timed_buffs() {
+    // Arrange
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default()).build();
+    let elixir = consumable(
+        "Elixir of Might",
+        1,
+        vec![ItemEffect::Buff {
+            modifier: Modifier::flat("Strength", 4),
+            rounds: 2,
+        }],
+    ).stack_limit(3)
+        .count(2)
+        .build();
+    let position = character.inventory.put(elixir.into()).unwrap();
+
+    // Act
+    character.consume(position).unwrap();
+
+    // Assert
+    assert_eq!(character.attribute(&Attribute::Strength), 5);
+    assert_eq!(character.timed_modifiers()[0].source, "Elixir of Might");
+    assert_eq!(character.inventory.get(position as i32).unwrap().quantity(), 1);
+    character.tick_modifiers();
+    assert_eq!(character.attribute(&Attribute::Strength), 5);
+    character.tick_modifiers();
+    assert_eq!(character.attribute(&Attribute::Strength), 1);
+    character.consume(position).unwrap();
+    assert!(character.inventory.get(position as i32).is_none());
+    assert_eq!(character.consume(position), Err(ConsumeError::NothingThere));
+}
+
+fn set_library() -> EquipmentSetLibrary {
+    EquipmentSetLibrary::from_yaml(include_str!("../../data/txt/sets.yml")).unwrap()
+}
+
+const REGALIA: &str = "Iahu's Regalia";
+
+#[test]
+fn set_bonuses_apply_by_tier() {
+    // Arrange
+    let crown = equipment("Crown", 1, Slot::Head, vec![]).part_of(REGALIA).build();
+    let robe = equipment("Robe", 2, Slot::Torso, vec![]).part_of(REGALIA).build();
+    let sandals = equipment("Sandals", 1, Slot::Feet, vec![]).part_of(REGALIA).build();
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default())
+        .sets(&set_library())
+        .build();
+
+    // Act & Assert
+    assert_eq!(character.display_item(&crown), "Crown, Iahu's Regalia (0/4)");
+    character.equip(crown.clone()).unwrap();
+    assert_eq!(character.display_item(&crown), "Crown, Iahu's Regalia (1/4)");
+    assert_eq!(character.attribute(&Attribute::Constitution), 1);
+
+    character.equip(robe).unwrap();
+    assert_eq!(character.attribute(&Attribute::Constitution), 3);
+    assert_eq!(character.damage(), 1);
+    assert_eq!(
+        character.attribute_breakdown(Attribute::Constitution).to_string(),
+        "Constitution 3: base 1, +2 from Iahu's Regalia (2/4)"
+    );
+
+    character.equip(sandals).unwrap();
+    assert_eq!(character.damage(), 4);
+    assert_eq!(character.equipment().pieces_worn("Iahu's Regalia"), 3);
+
+    let head = character.equipment().find_by_id(crown.id()).unwrap();
+    character.unequip(head);
+    assert_eq!(character.damage(), 1);
+}
+
+#[test]
+fn identical_set_pieces_count_once() {
+    let ring = equipment("Ring", 1, Slot::Hand, vec![]).part_of(REGALIA).build();
+    let sets = set_library();
+    let mut character = CharacterBuilder::new(8, CharacterAttributes::default())
+        .sets(&sets)
+        .build();
+
+    character.equip(ring.clone()).unwrap();
+    character.equip(ring.clone()).unwrap();
+
+    assert_eq!(
+        character.equipment().set_progress(&ring, &sets),
+        Some("Iahu's Regalia (1/4)".to_owned())
+    );
+    assert_eq!(character.attribute(&Attribute::Constitution), 1);
+    assert!(character.set_bonuses().is_empty());
+}
+
+#[test]
+fn character_sheet_gathers_everything() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    attributes.set(Attribute::Endurance, 4);
+    let mut character = CharacterBuilder::new(8, attributes)
+        .named("Bob")
+        .progression(LevelCurve::Linear { per_level: 100 }, 1)
+        .skill(skill_library().get("Power Strike").unwrap())
+        .sets(&set_library())
+        .build();
+    let spear = equipment("Spear", 4, Slot::Hand, vec![])
+        .two_handed()
+        .weight(5)
+        .build();
+    let helmet = equipment(
+        "Helmet",
+        2,
+        Slot::Head,
+        vec![ItemEffect::AttributeModifier(Attribute::Constitution, 2)],
+    ).part_of(REGALIA)
+        .weight(3)
+        .build();
+    let arrows = consumable("arrow", 1, vec![]).stack_limit(20).count(3).build();
+    let mut quiver = container("Quiver", 2, 4).build();
+    quiver.put(arrows.into());
+    character.equip(spear).unwrap();
+    character.equip(helmet).unwrap();
+    character.inventory.put(quiver.into());
+    character.gain_experience(150, &SkillLibrary::default());
+
+    // Act
+    let sheet = character.sheet();
+
+    // Assert
+    assert_eq!(
+        sheet.to_string(),
+        "Bob, level 2
+Experience: 150 (50 to next level)
+Unspent attribute points: 1
+Life: 3/3
+Stamina: 4/4
+Attributes:
+  Strength 3: base 3
+  Constitution 3: base 1, +2 from Helmet
+  Endurance 4: base 4
+  Swiftness 1: base 1
+Equipment:
+  hand: Spear
+  hand: Spear (also)
+  head: Helmet, Iahu's Regalia (1/4)
+  torso: -
+  feet: -
+Inventory:
+  [0] a Quiver (0 wt)
+    [0] three arrows (0 wt)
+Load: 8/30 (Unburdened)
+Actions per round: 1
+Actions: Attack, Power Strike
+Skills:
+  Power Strike (3 stamina)
+"
+    );
+    let json = ::serde_json::to_string(&sheet).unwrap();
+    let loaded: CharacterSheet = ::serde_json::from_str(&json).unwrap();
+    assert_eq!(loaded, sheet);
+
+    // Skills that queue_skill would reject are not offered
+    character.spend_stamina(2);
+    assert!(character.queue_skill("Power Strike").is_err());
+    assert_eq!(character.sheet().actions, vec!["Attack".to_owned()]);
+}
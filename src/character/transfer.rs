@@ -0,0 +1,56 @@
+use super::*;
+
+/// Reasons why an item could not be moved between the inventory and the worn equipment.
+#[derive(Clone, Debug)]
+pub enum TransferError {
+    /// There is no item at the inventory position or in the slot.
+    NothingThere,
+    /// Only equipment can be worn.
+    NotEquipment,
+    CannotEquip(EquipError),
+    /// The items that would be moved into the inventory do not fit.
+    InventoryFull,
+}
+
+impl Character {
+    /// Equips the item at the inventory position and moves all displaced items
+    /// into the inventory. Either everything is moved or nothing changes.
+    /// Returns the inventory positions of the displaced items.
+    pub fn equip_from_inventory(&mut self, pos: usize) -> Result<Vec<usize>, TransferError> {
+        let mut staged = self.clone();
+        let item = match staged.inventory.take(pos as i32) {
+            Some(Item::Equipment(item)) => item,
+            Some(_) => return Err(TransferError::NotEquipment),
+            None => return Err(TransferError::NothingThere),
+        };
+        let displaced = staged.equip(item).map_err(TransferError::CannotEquip)?;
+        let mut positions = vec![];
+        for item in displaced {
+            match staged.inventory.put(item.into()) {
+                Some(pos) => positions.push(pos),
+                None => return Err(TransferError::InventoryFull),
+            }
+        }
+        *self = staged;
+        Ok(positions)
+    }
+    /// Unequips the item occupying the slot and moves it into the inventory.
+    /// Nothing changes if the item does not fit. Returns the inventory position of the item.
+    pub fn unequip_to_inventory(&mut self, slot_idx: usize) -> Result<usize, TransferError> {
+        let owner = self.equipment.owner_of(slot_idx);
+        let fits = match owner.and_then(|idx| self.equipment.at(idx)) {
+            Some(item) => self.inventory.find_space(item.size()).is_some(),
+            None => return Err(TransferError::NothingThere),
+        };
+        if !fits {
+            return Err(TransferError::InventoryFull);
+        }
+        let item = self
+            .unequip(slot_idx)
+            .expect("slot was checked to be occupied");
+        Ok(self
+            .inventory
+            .put(item.into())
+            .expect("space was checked to be available"))
+    }
+}
@@ -1,16 +1,27 @@
 mod results;
+mod recap;
+mod variety;
 
 pub use self::results::Results;
+pub use self::recap::*;
+pub use self::variety::*;
 
 use self::results::*;
 use super::Display;
 use std::cmp::max;
 use item::Equipment;
+use skill::SkillEffect;
+use character::Modifier;
+use stat::Formula;
 
 /// Combat state, ie. information retained between combat rounds.
 pub struct Combat {
     pub duration: i32,
     pub results: Results,
+    /// Running summary of the combat, also included in the end results.
+    pub recap: Recap,
+    /// Keeps the narration from repeating itself.
+    pub variety: Variety,
 }
 
 pub trait Combatant: Display {
@@ -21,10 +32,100 @@ pub trait Combatant: Display {
     fn damage(&self) -> i32;
     // FIXME: teporary, used to find the item that the combatant most likely uses for hitting stuff
     fn best_weapon(&self) -> &Equipment;
-    // TODO: do something about stamina
+    /// Pays the costs of the skill and returns its effects, or None if the
+    /// skill can't be used right now.
+    fn use_skill(&mut self, _skill: &str) -> Option<Vec<SkillEffect>> {
+        None
+    }
+    /// Called after every combat round, eg. to tick cooldowns.
+    fn end_round(&mut self) {}
+    /// Adds a buff or a debuff. Ignored by combatants without modifiable attributes.
+    fn apply_timed_modifier(&mut self, _modifier: Modifier, _rounds: u32, _source: &str) {}
+    /// Uses the consumable at the inventory position and returns its name, or
+    /// None if there is no consumable to use.
+    fn use_consumable(&mut self, _position: usize) -> Option<String> {
+        None
+    }
+    /// Performs every action in the action buffer.
+    fn perform_actions(&mut self) -> Performance {
+        let mut performance = Performance::default();
+        for action in self.action_buffer().actions() {
+            let (effects, source) = match *action {
+                Action::Attack => (None, ""),
+                Action::Skill(ref name) => (self.use_skill(name), name.as_str()),
+                Action::Consume(position) => match self.use_consumable(position) {
+                    Some(name) => {
+                        performance.used.push(name);
+                        continue;
+                    }
+                    None => (None, ""),
+                },
+            };
+            if effects.is_some() {
+                performance.used.push(source.to_owned());
+            }
+            match effects {
+                None => performance.hits.push(Hit {
+                    damage: self.damage(),
+                    source: self.best_weapon().name(),
+                    skill: false,
+                }),
+                Some(effects) => for effect in effects {
+                    match effect {
+                        SkillEffect::Strike { damage_percent, bonus_damage } => {
+                            let damage = self.damage() * damage_percent / 100 + bonus_damage;
+                            performance.hits.push(Hit {
+                                damage: max(damage, 0),
+                                source: source.to_owned(),
+                                skill: true,
+                            });
+                        }
+                        SkillEffect::Heal(amount) => performance.healing += amount,
+                        SkillEffect::Buff { modifier, rounds } => {
+                            self.apply_timed_modifier(modifier, rounds, source)
+                        }
+                        SkillEffect::Debuff { modifier, rounds } => {
+                            performance.debuffs.push((modifier, rounds, source.to_owned()))
+                        }
+                    }
+                },
+            }
+        }
+        performance
+    }
+}
+
+/// Everything a combatant did during one round.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Performance {
+    /// Each hit dealt to the opponent.
+    pub hits: Vec<Hit>,
+    /// Life restored to the combatant itself.
+    pub healing: i32,
+    /// Modifiers to apply to the opponent, with their durations and sources.
+    pub debuffs: Vec<(Modifier, u32, String)>,
+    /// Names of the skills and consumables used.
+    pub used: Vec<String>,
+}
+
+impl Performance {
+    /// Total damage of all hits.
+    pub fn damage(&self) -> i32 {
+        self.hits.iter().map(|hit| hit.damage).sum()
+    }
 }
 
-#[derive(Clone)]
+/// A hit dealt to the opponent and what dealt it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hit {
+    pub damage: i32,
+    /// Name of the weapon or the skill, eg. "fist" or "Power Strike".
+    pub source: String,
+    /// True if a skill dealt the hit.
+    pub skill: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ActionBuffer {
     actions: Vec<Action>,
     max_actions: usize,
@@ -70,6 +171,62 @@ impl ActionBuffer {
     }
 }
 
+impl ActionBuffer {
+    /// Creates a buffer where every available action is the given one.
+    pub fn filled(max_actions: usize, action: &Action) -> ActionBuffer {
+        let mut buffer = ActionBuffer::new(max_actions);
+        while buffer.push(action) {}
+        buffer
+    }
+    pub fn max_actions(&self) -> usize {
+        self.max_actions
+    }
+    pub fn actions(&self) -> &[Action] {
+        self.actions.as_slice()
+    }
+}
+
+/// Converts Swiftness into the number of actions per round:
+/// `base + (swiftness - 1) / swiftness_per_action`, limited to `minimum..=maximum`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ActionFormula {
+    pub base: i32,
+    /// Points of Swiftness required for each action past the base.
+    pub swiftness_per_action: i32,
+    pub minimum: i32,
+    pub maximum: i32,
+}
+
+impl ActionFormula {
+    /// Evaluates the formula with the given Swiftness.
+    pub fn actions(&self, swiftness: i32) -> usize {
+        // The formula refers to Swiftness only
+        max(self.formula().evaluate(&|_| swiftness), 0) as usize
+    }
+    /// The same conversion as a stat formula, used for the "ActionsPerRound" stat.
+    pub fn formula(&self) -> Formula {
+        let source = format!(
+            "min(max({} + max(Swiftness - 1, 0) / {}, {}), {})",
+            self.base,
+            max(self.swiftness_per_action, 1),
+            self.minimum,
+            max(self.minimum, self.maximum)
+        );
+        Formula::parse(&source).expect("action formulas are valid")
+    }
+}
+
+impl Default for ActionFormula {
+    fn default() -> Self {
+        ActionFormula {
+            base: 1,
+            swiftness_per_action: 3,
+            minimum: 1,
+            maximum: 4,
+        }
+    }
+}
+
 impl Default for ActionBuffer {
     fn default() -> ActionBuffer {
         ActionBuffer {
@@ -80,11 +237,16 @@ impl Default for ActionBuffer {
 }
 
 /// Things that the combatants may do in the combat.
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 pub enum Action {
     //Evade,
     //Block,
     Attack,
+    /// Uses the named skill, falls back to an attack if the skill can't be used.
+    Skill(String),
+    /// Uses the consumable at the inventory position, falls back to an attack
+    /// if there is nothing to consume.
+    Consume(usize),
 }
 
 impl<'a> From<&'a Action> for String {
@@ -92,6 +254,8 @@ impl<'a> From<&'a Action> for String {
         use Action::*;
         match *action {
             Attack => "Attack".to_owned(),
+            Skill(ref name) => name.clone(),
+            Consume(position) => format!("Consume {}", position),
         }
     }
 }
@@ -99,9 +263,11 @@ impl<'a> From<&'a Action> for String {
 /// All that actually happened (to a target).
 #[derive(Clone, Eq, PartialEq)]
 enum Outcome {
+    // TODO: nothing misses yet
+    #[allow(dead_code)]
     Miss,
     //Block,
-    Hit(i32),
+    Hit(Hit),
     //Crit(i32),
     Killed,
 }
@@ -111,8 +277,15 @@ impl Combat {
         Combat {
             duration: 0,
             results: ResultsBuilder::new(combatant_a, combatant_b).build_begin(),
+            recap: Recap::new(combatant_a, combatant_b),
+            variety: Variety::default(),
         }
     }
+    /// Sets the number of recent narration choices that are not repeated.
+    pub fn variety_window(mut self, window: usize) -> Combat {
+        self.variety.set_window(window);
+        self
+    }
     pub fn has_ended(&self) -> bool {
         if let Results::End { .. } = self.results {
             true
@@ -151,54 +324,58 @@ impl Combat {
         }
         // Do combat calculations
         let results = {
-            use Action::*;
-
-            let a_buffer = &a.action_buffer();
-            let b_buffer = &a.action_buffer();
-
-            // Count number of different actions
-            let num_atks_by_a = a_buffer.count(&Attack) as i32;
-            let num_atks_by_b = b_buffer.count(&Attack) as i32;
-
             // Resolve outcomes
-            let num_hits_to_a = num_atks_by_b;
-            let num_hits_to_b = num_atks_by_a;
-            let num_misses_to_a = 0;
-            let num_misses_to_b = 0;
-
-            use self::Outcome::*;
-            let mut outcomes_a = vec![Miss; num_misses_to_a as usize];
-            for _ in 0..num_hits_to_a {
-                outcomes_a.push(Hit(b.damage()));
-            }
-            let mut outcomes_b = vec![Miss; num_misses_to_b as usize];
-            for _ in 0..num_hits_to_b {
-                outcomes_b.push(Hit(a.damage()));
-            }
+            let performance_a = a.perform_actions();
+            let performance_b = b.perform_actions();
+            let (damage_a, damage_b) = (performance_a.damage(), performance_b.damage());
+            let mut outcomes_a: Vec<Outcome> =
+                performance_b.hits.into_iter().map(Outcome::Hit).collect();
+            let mut outcomes_b: Vec<Outcome> =
+                performance_a.hits.into_iter().map(Outcome::Hit).collect();
 
             // TODO: make combat cooler by taking into account hits with each item used as a weapon.
-            // TODO: use outcomes to do the calculation
-            // Resolve a -> b
-            let a_life = a.life();
-            a.set_life(a_life - num_hits_to_a * b.damage());
             // Resolve b -> a
+            let a_life = a.life();
+            a.set_life(a_life + performance_a.healing - damage_b);
+            // Resolve a -> b
             let b_life = b.life();
-            b.set_life(b_life - num_hits_to_b * a.damage());
+            b.set_life(b_life + performance_b.healing - damage_a);
+            a.end_round();
+            b.end_round();
+            // Debuffs are applied after the round has been counted so that they
+            // last for the given number of the opponent's rounds
+            for (modifier, rounds, source) in performance_a.debuffs {
+                b.apply_timed_modifier(modifier, rounds, &source);
+            }
+            for (modifier, rounds, source) in performance_b.debuffs {
+                a.apply_timed_modifier(modifier, rounds, &source);
+            }
 
             if !a.can_combat() {
-                outcomes_a.push(Killed);
+                outcomes_a.push(Outcome::Killed);
             }
             if !b.can_combat() {
-                outcomes_b.push(Killed);
+                outcomes_b.push(Outcome::Killed);
             }
 
-            let builder = ResultsBuilder::new(a, b).write_round(&outcomes_a, &outcomes_b);
-            match (a.can_combat(), b.can_combat()) {
-                (true, true) => builder.build_round(),
-                (true, false) => builder.build_end(CombatantId::A, self.duration),
-                (false, true) => builder.build_end(CombatantId::B, self.duration),
+            self.recap.record_used(CombatantId::A, &performance_a.used);
+            self.recap.record_used(CombatantId::B, &performance_b.used);
+            self.recap.record_round(&outcomes_a, &outcomes_b);
+
+            let builder = ResultsBuilder::new(a, b).write_round(&outcomes_a, &outcomes_b, &mut self.variety);
+            let winner = match (a.can_combat(), b.can_combat()) {
+                (true, true) => None,
+                (true, false) => Some(CombatantId::A),
+                (false, true) => Some(CombatantId::B),
                 // TODO: improve handling of ties
-                (false, false) => builder.build_end(CombatantId::B, self.duration),
+                (false, false) => Some(CombatantId::B),
+            };
+            match winner {
+                None => builder.build_round(),
+                Some(winner) => {
+                    self.recap.record_end(&outcomes_a, &outcomes_b);
+                    builder.build_end(winner, self.duration, self.recap.clone())
+                }
             }
         };
 
@@ -215,7 +392,7 @@ impl Combat {
 }
 
 // TODO: this shouldn't be a part of the public interface
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub enum CombatantId {
     A,
     B,
@@ -0,0 +1,190 @@
+use super::*;
+use display::noun::{capitalize, number_word, pluralize};
+
+/// Summary of a whole combat. A is the player, B is the opponent.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Recap {
+    /// Number of rounds fought.
+    pub rounds: i32,
+    pub a: SideRecap,
+    pub b: SideRecap,
+    /// The blow that ended the combat, if it has ended with one side standing.
+    pub killing_blow: Option<KillingBlow>,
+    /// True if both sides fell in the same round.
+    #[serde(default)]
+    pub draw: bool,
+}
+
+/// What one side of the combat did and suffered.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SideRecap {
+    /// Name with an article as it appears in narration, eg. "the goblin".
+    pub name: String,
+    pub damage_dealt: i32,
+    pub damage_taken: i32,
+    pub hits: usize,
+    pub misses: usize,
+    /// Names of weapons, consumables and skills used during the combat, in
+    /// order of first use.
+    pub items_used: Vec<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct KillingBlow {
+    pub by: CombatantId,
+    /// Name of the weapon or the skill that struck the blow.
+    pub weapon: String,
+    /// True if a skill struck the blow.
+    #[serde(default)]
+    pub skill: bool,
+    pub damage: i32,
+    /// The round in which the blow was struck, starting from 1.
+    pub round: i32,
+}
+
+impl SideRecap {
+    fn new<T: Combatant>(combatant: &T) -> SideRecap {
+        SideRecap {
+            name: combatant.definite_name(),
+            damage_dealt: 0,
+            damage_taken: 0,
+            hits: 0,
+            misses: 0,
+            items_used: vec![],
+        }
+    }
+    fn use_item(&mut self, name: String) {
+        if !self.items_used.contains(&name) {
+            self.items_used.push(name);
+        }
+    }
+}
+
+impl Recap {
+    pub fn new<T: Combatant, U: Combatant>(a: &T, b: &U) -> Recap {
+        Recap {
+            rounds: 0,
+            a: SideRecap::new(a),
+            b: SideRecap::new(b),
+            killing_blow: None,
+            draw: false,
+        }
+    }
+    /// Records the outcomes of one round. `outcomes_a` are the outcomes of
+    /// blows against A and `outcomes_b` the outcomes of blows against B.
+    pub(super) fn record_round(&mut self, outcomes_a: &[Outcome], outcomes_b: &[Outcome]) {
+        self.rounds += 1;
+        Recap::record_blows(&mut self.b, &mut self.a, outcomes_a);
+        Recap::record_blows(&mut self.a, &mut self.b, outcomes_b);
+    }
+    /// Records the skills and consumables used by one side.
+    pub(super) fn record_used(&mut self, by: CombatantId, names: &[String]) {
+        let side = match by {
+            CombatantId::A => &mut self.a,
+            CombatantId::B => &mut self.b,
+        };
+        for name in names {
+            side.use_item(name.clone());
+        }
+    }
+    /// Records how the combat ended from the outcomes of the final round.
+    /// The killing blow is the last hit against the side that fell.
+    pub(super) fn record_end(&mut self, outcomes_a: &[Outcome], outcomes_b: &[Outcome]) {
+        let killed = |outcomes: &[Outcome]| outcomes.contains(&Outcome::Killed);
+        let (by, outcomes) = match (killed(outcomes_a), killed(outcomes_b)) {
+            (false, true) => (CombatantId::A, outcomes_b),
+            (true, false) => (CombatantId::B, outcomes_a),
+            (true, true) => {
+                self.draw = true;
+                return;
+            }
+            (false, false) => return,
+        };
+        let hit = outcomes.iter().rev().find_map(|outcome| match *outcome {
+            Outcome::Hit(ref hit) => Some(hit),
+            _ => None,
+        });
+        if let Some(hit) = hit {
+            self.killing_blow = Some(KillingBlow {
+                by,
+                weapon: hit.source.clone(),
+                skill: hit.skill,
+                damage: hit.damage,
+                round: self.rounds,
+            });
+        }
+    }
+    fn record_blows(attacker: &mut SideRecap, defender: &mut SideRecap, outcomes: &[Outcome]) {
+        for outcome in outcomes {
+            match *outcome {
+                Outcome::Miss => attacker.misses += 1,
+                Outcome::Hit(ref hit) => {
+                    attacker.hits += 1;
+                    attacker.damage_dealt += hit.damage;
+                    defender.damage_taken += hit.damage;
+                    attacker.use_item(hit.source.clone());
+                }
+                Outcome::Killed => {}
+            }
+        }
+    }
+    /// A short paragraph describing the combat for the journal.
+    pub fn narrate(&self) -> String {
+        let mut sentences = vec![format!(
+            "The fight lasted {}.",
+            counted(self.rounds as usize, "round")
+        )];
+        sentences.push(format!(
+            "You dealt {} damage with {} and {}, and took {} damage.",
+            self.a.damage_dealt,
+            counted(self.a.hits, "hit"),
+            counted(self.a.misses, "miss"),
+            self.a.damage_taken
+        ));
+        sentences.push(format!(
+            "{} landed {} and {}.",
+            capitalize(&self.b.name),
+            counted(self.b.hits, "hit"),
+            counted(self.b.misses, "miss")
+        ));
+        if let Some(ref blow) = self.killing_blow {
+            sentences.push(match (blow.by, blow.skill) {
+                (CombatantId::A, false) => format!(
+                    "You struck the killing blow with the {} in round {}.",
+                    blow.weapon, blow.round
+                ),
+                (CombatantId::A, true) => format!(
+                    "You struck the killing blow with {} in round {}.",
+                    blow.weapon, blow.round
+                ),
+                (CombatantId::B, false) => format!(
+                    "{} struck the killing blow with their {} in round {}.",
+                    capitalize(&self.b.name),
+                    blow.weapon,
+                    blow.round
+                ),
+                (CombatantId::B, true) => format!(
+                    "{} struck the killing blow with {} in round {}.",
+                    capitalize(&self.b.name),
+                    blow.weapon,
+                    blow.round
+                ),
+            });
+        }
+        if self.draw {
+            sentences.push(format!(
+                "You and {} struck each other down in round {}.",
+                self.b.name, self.rounds
+            ));
+        }
+        sentences.join(" ")
+    }
+}
+
+/// Eg. "one round" or "three misses".
+fn counted(count: usize, noun: &str) -> String {
+    match count {
+        1 => format!("{} {}", number_word(count), noun),
+        _ => format!("{} {}", number_word(count), pluralize(noun)),
+    }
+}
@@ -1,17 +1,23 @@
 use super::*;
 use rand::{thread_rng, Rng};
-use display::{Display, DisplayWeapon};
+use display::{Display, DisplayWeapon, NounKind};
+use display::noun::capitalize;
+use display::markup::Narration;
 use inflector::Inflector;
-use rustache::*;
-use std::io::Cursor;
+use super::variety::Variety;
+use std::collections::HashMap;
 
+/// Each variant carries the narration both as plain text in `log` and as a
+/// span tree tagged with semantic roles in `narration`.
 pub enum Results {
-    Begin { log: String },
-    Round { log: String },
+    Begin { log: String, narration: Narration },
+    Round { log: String, narration: Narration },
     End {
         log: String,
+        narration: Narration,
         winner: CombatantId,
         duration: i32,
+        recap: Recap,
     },
 }
 
@@ -19,7 +25,7 @@ pub enum Results {
 /// opponent.
 pub struct ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant, T: 'a, U: 'a {
     template_log: String,
-    data: HashBuilder<'a>,
+    data: HashMap<&'static str, String>,
     a: &'a T,
     b: &'a U,
 }
@@ -29,16 +35,22 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
     pub fn new(a: &'a T, b: &'a U) -> ResultsBuilder<'a, T, U> {
         let a_weapon = a.best_weapon();
         let b_weapon = b.best_weapon();
-        let str_builder = HashBuilder::new()
-                .insert("a_name", a.name())
-                .insert("b_name", b.name())
-                .insert("a_weapon", a_weapon.name())
-                .insert("a_weapon_action", a_weapon.display_offensive_action_1st())
-                .insert("b_weapon", b_weapon.name())
-                .insert("b_weapon_action", b_weapon.display_offensive_action_2nd());
+        let data = hashmap!(
+                "a_name" => a.name(),
+                "b_name" => b.name(),
+                "b_definite" => b.definite_name(),
+                "b_definite_capitalized" => capitalize(&b.definite_name()),
+                "b_pronoun" => object_pronoun(b).to_owned(),
+                "a_weapon" => a_weapon.name(),
+                "a_weapon_definite" => a_weapon.definite_name(),
+                "a_weapon_action" => a_weapon.display_offensive_action_1st(),
+                "b_weapon" => b_weapon.name(),
+                "b_weapon_indefinite" => b_weapon.indefinite_name(),
+                "b_weapon_action" => b_weapon.display_offensive_action_2nd(),
+                "b_weapon_action_base" => b_weapon.display_offensive_action_1st());
         ResultsBuilder {
             template_log: String::new(),
-            data: str_builder,
+            data,
             a, b
         }
     }
@@ -46,32 +58,63 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
     pub fn build_begin(mut self) -> Results {
         // The begin always looks the same, set the log to that.
         self.template_log = BEGIN.to_owned();
-        Results::Begin { log: self.fill_template() }
+        let narration = self.fill_template();
+        Results::Begin { log: narration.to_plain(), narration }
     }
     pub fn build_round(self) -> Results {
-        Results::Round { log: self.fill_template() }
+        let narration = self.fill_template();
+        Results::Round { log: narration.to_plain(), narration }
     }
-    pub fn build_end(self, winner: CombatantId, duration: i32) -> Results {
+    pub fn build_end(self, winner: CombatantId, duration: i32, recap: Recap) -> Results {
+        let narration = self.fill_template();
         Results::End {
-            log: self.fill_template(),
-            winner: winner,
-            duration: duration,
+            log: narration.to_plain(),
+            narration,
+            winner,
+            duration,
+            recap,
         }
     }
     /// Fills in the variables into the template.
-    fn fill_template(&self) -> String {
-        let mut out = Cursor::new(Vec::new());
-        self.data.render(&self.template_log, &mut out).unwrap();
-        String::from_utf8(out.into_inner()).unwrap()
+    fn fill_template(&self) -> Narration {
+        Narration::from_template(&self.template_log, &self.data)
     }
-    pub fn write_round(mut self, a_outcomes: &Vec<Outcome>, b_outcomes: &Vec<Outcome>) -> ResultsBuilder<'a, T, U> {
-        let (mut sentences, a_killed, b_killed) = ResultsBuilder::<T, U>::outcome_sentences(a_outcomes, b_outcomes);
+    pub fn write_round(
+        mut self,
+        a_outcomes: &[Outcome],
+        b_outcomes: &[Outcome],
+        variety: &mut Variety,
+    ) -> ResultsBuilder<'a, T, U> {
+        let mut rng = thread_rng();
+        let (mut kinds, a_killed, b_killed) = ResultsBuilder::<T, U>::outcome_kinds(a_outcomes, b_outcomes);
 
         // Randomize the order of sentences
-        {
-            let slice: &mut [String] = sentences.as_mut_slice();
-            thread_rng().shuffle(slice);
+        rng.shuffle(kinds.as_mut_slice());
+
+        // Pick variants and synonyms that have not been used recently
+        let mut sentences: Vec<String> = kinds
+            .iter()
+            .map(|kind| {
+                let variant = variety.pick(kind.variants(), &mut rng);
+                if variant.contains("{{wounding}}") {
+                    variant.replace("{{wounding}}", variety.pick(&WOUNDING, &mut rng))
+                } else {
+                    variant.to_owned()
+                }
+            })
+            .collect();
+
+        // Occasionally tie the round to the previous one
+        if rng.gen::<f32>() < CONNECTIVE_CHANCE {
+            let eligible = ResultsBuilder::<T, U>::eligible_connectives(&kinds, variety.previous_round());
+            let words: Vec<&'static str> = eligible.iter().map(|&(_, word)| word).collect();
+            if !words.is_empty() {
+                let word = variety.pick(&words, &mut rng);
+                let &(idx, _) = eligible.iter().find(|&&(_, w)| w == word).unwrap();
+                sentences[idx] = with_connective(word, &sentences[idx]);
+            }
         }
+        variety.set_previous_round(kinds);
 
         // Add the kill sentence to the end
         if let Some(s) = ResultsBuilder::<T, U>::kill_sentence(a_killed, b_killed) {
@@ -81,16 +124,16 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
         self
     }
     // Internals
-    fn outcome_sentences(a_outcomes: &Vec<Outcome>, b_outcomes: &Vec<Outcome>) -> (Vec<String>, bool, bool) {
-        let mut sentences = Vec::with_capacity(a_outcomes.len() + b_outcomes.len());
+    fn outcome_kinds(a_outcomes: &[Outcome], b_outcomes: &[Outcome]) -> (Vec<SentenceKind>, bool, bool) {
+        let mut kinds = Vec::with_capacity(a_outcomes.len() + b_outcomes.len());
         let mut a_killed = false;
         for outcome in a_outcomes {
             match *outcome {
                 Outcome::Miss => {
-                    sentences.push(THEY_MISS.to_owned());
+                    kinds.push(SentenceKind::TheyMiss);
                 },
-                Outcome::Hit(_) => {
-                    sentences.push(THEY_HIT.to_owned());
+                Outcome::Hit(..) => {
+                    kinds.push(SentenceKind::TheyHit);
                 },
                 Outcome::Killed => {
                     a_killed = true;
@@ -102,10 +145,10 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
         for outcome in b_outcomes {
             match *outcome {
                 Outcome::Miss => {
-                    sentences.push(YOU_MISS.to_owned());
+                    kinds.push(SentenceKind::YouMiss);
                 },
-                Outcome::Hit(_) => {
-                    sentences.push(YOU_HIT.to_owned());
+                Outcome::Hit(..) => {
+                    kinds.push(SentenceKind::YouHit);
                 },
                 Outcome::Killed => {
                     b_killed = true;
@@ -113,7 +156,28 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
                 }
             }
         }
-        (sentences, a_killed, b_killed)
+        (kinds, a_killed, b_killed)
+    }
+    /// Returns the connectives that make sense for this round and the index of
+    /// the sentence each would be attached to.
+    fn eligible_connectives(kinds: &[SentenceKind], previous: &[SentenceKind]) -> Vec<(usize, &'static str)> {
+        let mut eligible = vec![];
+        if previous.is_empty() {
+            return eligible;
+        }
+        if let Some(first) = kinds.first() {
+            if previous.contains(first) {
+                eligible.push((0, AGAIN));
+            }
+        }
+        if kinds.len() > 1 {
+            eligible.push((1, MEANWHILE));
+        }
+        // The actor of the sentence was hit in the previous round
+        if let Some(idx) = kinds.iter().position(|kind| previous.contains(&kind.hit_by_opponent())) {
+            eligible.push((idx, STAGGERING));
+        }
+        eligible
     }
     fn kill_sentence(a_killed: bool, b_killed: bool) -> Option<String> {
         match (a_killed, b_killed) {
@@ -130,23 +194,127 @@ impl<'a, T, U> ResultsBuilder<'a, T, U> where T: Combatant, U: Combatant {
 
 
 
-static BEGIN: &str = "The {{b_name}} notices you and attacks.";
-static YOU_MISS: &str = "You attempt to {{a_weapon_action}} the {{b_name}} with the {{a_weapon}} but \
-                        miss.";
-static THEY_MISS: &str = "The {{b_name}} attempts to {{b_weapon_action}} you with a {{b_weapon}} but \
-                         misses.";
-static YOU_HIT: &str = "You {{a_weapon_action}} the {{b_name}} with the {{a_weapon}}, wounding it.";
-static THEY_HIT: &str = "The {{b_name}} {{b_weapon_action}} you with a {{b_weapon}}, wounding you.";
-static YOU_KILL: &str = "You {{a_weapon_action}} the {{b_name}} with the {{a_weapon}} until you are \
-                        certain that you are the only living thing in the room. You are safe now.";
-static THEY_KILL: &str = "The {{b_name}} {{b_weapon_action}} you with their {{b_weapon}}, causing you \
-                         to feel lightheaded. You suddenly lose consciousness. You die.";
-static BOTH_KILL: &str = "The {{b_name}} {{b_weapon_action}} you with their {{b_weapon}}, causing you \
-                         to feel lightheaded. You {{a_weapon_action}} the {{b_name}} with the \
-                         {{a_weapon}}, causing yet another untimely death. Soon after the \
-                         {{b_name}}'s death you suddenly collapse. Despite your best efforts, you \
-                         are unable to stop the hemorrhaging and quickly (try to) make peace with \
-                         your god.";
+// Role sections, eg. {{#actor}}...{{/actor}}, tag the text for rich narration.
+static BEGIN: &str = "{{#actor}}{{b_definite_capitalized}}{{/actor}} notices {{#target}}you{{/target}} \
+                     and attacks.";
+static YOU_MISS: [&str; 3] = [
+    "{{#actor}}You{{/actor}} attempt to {{a_weapon_action}} {{#target}}{{b_definite}}{{/target}} \
+     with {{#weapon}}{{a_weapon_definite}}{{/weapon}} but miss.",
+    "{{#actor}}You{{/actor}} swing {{#weapon}}{{a_weapon_definite}}{{/weapon}} at \
+     {{#target}}{{b_definite}}{{/target}} but only hit air.",
+    "{{#target}}{{b_definite_capitalized}}{{/target}} dodges as {{#actor}}you{{/actor}} try to \
+     {{a_weapon_action}} {{b_pronoun}} with {{#weapon}}{{a_weapon_definite}}{{/weapon}}.",
+];
+static THEY_MISS: [&str; 3] = [
+    "{{#actor}}{{b_definite_capitalized}}{{/actor}} attempts to {{b_weapon_action_base}} \
+     {{#target}}you{{/target}} with {{#weapon}}{{b_weapon_indefinite}}{{/weapon}} but misses.",
+    "{{#actor}}{{b_definite_capitalized}}{{/actor}} swings {{#weapon}}{{b_weapon_indefinite}}{{/weapon}} \
+     at {{#target}}you{{/target}}, but you step aside.",
+    "{{#target}}You{{/target}} duck as {{#actor}}{{b_definite}}{{/actor}} tries to \
+     {{b_weapon_action_base}} you with {{#weapon}}{{b_weapon_indefinite}}{{/weapon}}.",
+];
+static YOU_HIT: [&str; 3] = [
+    "{{#actor}}You{{/actor}} {{a_weapon_action}} {{#target}}{{b_definite}}{{/target}} with \
+     {{#weapon}}{{a_weapon_definite}}{{/weapon}}, {{#damage}}{{wounding}} {{b_pronoun}}{{/damage}}.",
+    "{{#actor}}You{{/actor}} land a blow on {{#target}}{{b_definite}}{{/target}} with \
+     {{#weapon}}{{a_weapon_definite}}{{/weapon}}, {{#damage}}{{wounding}} {{b_pronoun}}{{/damage}}.",
+    "{{#actor}}You{{/actor}} {{a_weapon_action}} {{#target}}{{b_definite}}{{/target}} with \
+     {{#weapon}}{{a_weapon_definite}}{{/weapon}} and {{#damage}}draw blood{{/damage}}.",
+];
+static THEY_HIT: [&str; 3] = [
+    "{{#actor}}{{b_definite_capitalized}}{{/actor}} {{b_weapon_action}} {{#target}}you{{/target}} \
+     with {{#weapon}}{{b_weapon_indefinite}}{{/weapon}}, {{#damage}}{{wounding}} you{{/damage}}.",
+    "{{#actor}}{{b_definite_capitalized}}{{/actor}} catches {{#target}}you{{/target}} with \
+     {{#weapon}}{{b_weapon_indefinite}}{{/weapon}}, {{#damage}}{{wounding}} you{{/damage}}.",
+    "{{#actor}}{{b_definite_capitalized}}{{/actor}} {{b_weapon_action}} {{#target}}you{{/target}} \
+     with {{#weapon}}{{b_weapon_indefinite}}{{/weapon}} and {{#damage}}draws blood{{/damage}}.",
+];
+/// Synonyms for {{wounding}} in the hit sentences.
+static WOUNDING: [&str; 3] = ["wounding", "injuring", "hurting"];
+
+/// The chance that a round is tied to the previous one with a connective.
+const CONNECTIVE_CHANCE: f32 = 0.35;
+static AGAIN: &str = "Again,";
+static MEANWHILE: &str = "Meanwhile,";
+static STAGGERING: &str = "Staggering,";
+static YOU_KILL: &str = "{{#actor}}You{{/actor}} {{a_weapon_action}} {{#target}}{{b_definite}}{{/target}} \
+                        with {{#weapon}}{{a_weapon_definite}}{{/weapon}} until you are certain \
+                        that {{#death}}you are the only living thing in the room{{/death}}. You \
+                        are safe now.";
+static THEY_KILL: &str = "{{#actor}}{{b_definite_capitalized}}{{/actor}} {{b_weapon_action}} \
+                         {{#target}}you{{/target}} with their {{#weapon}}{{b_weapon}}{{/weapon}}, \
+                         {{#damage}}causing you to feel lightheaded{{/damage}}. You suddenly lose \
+                         consciousness. {{#death}}You die.{{/death}}";
+static BOTH_KILL: &str = "{{#actor}}{{b_definite_capitalized}}{{/actor}} {{b_weapon_action}} \
+                         {{#target}}you{{/target}} with their {{#weapon}}{{b_weapon}}{{/weapon}}, \
+                         {{#damage}}causing you to feel lightheaded{{/damage}}. \
+                         {{#actor}}You{{/actor}} {{a_weapon_action}} \
+                         {{#target}}{{b_definite}}{{/target}} with \
+                         {{#weapon}}{{a_weapon_definite}}{{/weapon}}, \
+                         {{#death}}causing yet another untimely death{{/death}}. Soon after \
+                         {{b_definite}}'s death you suddenly collapse. Despite your best efforts, \
+                         you are unable to stop the hemorrhaging and quickly (try to) \
+                         {{#death}}make peace with your god{{/death}}.";
+
+/// What happened in a single sentence of a round.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SentenceKind {
+    YouHit,
+    YouMiss,
+    TheyHit,
+    TheyMiss,
+}
+
+impl SentenceKind {
+    fn variants(&self) -> &'static [&'static str] {
+        use self::SentenceKind::*;
+        match *self {
+            YouHit => &YOU_HIT,
+            YouMiss => &YOU_MISS,
+            TheyHit => &THEY_HIT,
+            TheyMiss => &THEY_MISS,
+        }
+    }
+    /// The kind of sentence in which the actor of this sentence gets hit.
+    fn hit_by_opponent(&self) -> SentenceKind {
+        use self::SentenceKind::*;
+        match *self {
+            YouHit | YouMiss => TheyHit,
+            TheyHit | TheyMiss => YouHit,
+        }
+    }
+}
+
+/// Prefixes the sentence template with a connective, eg. "Again, you hit..."
+fn with_connective(connective: &str, sentence: &str) -> String {
+    // Skip the role sections that open the sentence
+    let mut start = 0;
+    while sentence[start..].starts_with("{{#") {
+        match sentence[start..].find("}}") {
+            Some(end) => start += end + 2,
+            None => break,
+        }
+    }
+    let (open, rest) = sentence.split_at(start);
+    let rest = if rest.starts_with("{{b_definite_capitalized}}") {
+        rest.replacen("{{b_definite_capitalized}}", "{{b_definite}}", 1)
+    } else {
+        let mut chars = rest.chars();
+        match chars.next() {
+            Some(first) => first.to_lowercase().chain(chars).collect(),
+            None => String::new(),
+        }
+    };
+    format!("{} {}{}", connective, open, rest)
+}
+
+/// Refers to named opponents as "them" and to everything else as "it".
+fn object_pronoun<C: Combatant>(combatant: &C) -> &'static str {
+    match combatant.noun_kind() {
+        NounKind::Proper => "them",
+        NounKind::Common => "it",
+    }
+}
 
 /*
 ## Example (25.8.-17)
@@ -0,0 +1,80 @@
+use rand::Rng;
+use std::collections::VecDeque;
+use super::results::SentenceKind;
+
+/// Number of recent choices that are avoided by default.
+pub const DEFAULT_VARIETY_WINDOW: usize = 6;
+
+/// Remembers recently used narration variants, synonyms and connectives so
+/// that they are not repeated within a window of choices.
+#[derive(Clone, Debug)]
+pub struct Variety {
+    window: usize,
+    recent: VecDeque<&'static str>,
+    /// What happened in the previous round, used to pick connectives.
+    previous_round: Vec<SentenceKind>,
+}
+
+impl Variety {
+    pub fn new(window: usize) -> Variety {
+        Variety {
+            window,
+            recent: VecDeque::with_capacity(window),
+            previous_round: vec![],
+        }
+    }
+    pub fn window(&self) -> usize {
+        self.window
+    }
+    pub fn set_window(&mut self, window: usize) {
+        self.window = window;
+        self.forget_oldest();
+    }
+    /// Returns true if the choice has been made within the window.
+    pub fn is_recent(&self, choice: &str) -> bool {
+        self.recent.iter().any(|&c| c == choice)
+    }
+    /// Picks one of the options at random, avoiding recently picked ones. If
+    /// all options are recent, picks the one that was used longest ago.
+    pub fn pick<R: Rng>(&mut self, options: &[&'static str], rng: &mut R) -> &'static str {
+        let fresh: Vec<&'static str> = options
+            .iter()
+            .cloned()
+            .filter(|option| !self.is_recent(option))
+            .collect();
+        let choice = match rng.choose(&fresh) {
+            Some(&choice) => choice,
+            None => self.least_recent(options),
+        };
+        self.remember(choice);
+        choice
+    }
+    pub(super) fn previous_round(&self) -> &[SentenceKind] {
+        self.previous_round.as_slice()
+    }
+    pub(super) fn set_previous_round(&mut self, kinds: Vec<SentenceKind>) {
+        self.previous_round = kinds;
+    }
+    fn least_recent(&self, options: &[&'static str]) -> &'static str {
+        options
+            .iter()
+            .cloned()
+            .min_by_key(|&option| self.recent.iter().rposition(|&c| c == option))
+            .expect("cannot pick from an empty set of options")
+    }
+    fn remember(&mut self, choice: &'static str) {
+        self.recent.push_back(choice);
+        self.forget_oldest();
+    }
+    fn forget_oldest(&mut self) {
+        while self.recent.len() > self.window {
+            self.recent.pop_front();
+        }
+    }
+}
+
+impl Default for Variety {
+    fn default() -> Variety {
+        Variety::new(DEFAULT_VARIETY_WINDOW)
+    }
+}
@@ -0,0 +1,59 @@
+use super::noun::capitalize;
+
+/// Verbs that do not follow the regular third person rules.
+static IRREGULAR_VERBS: [(&str, &str); 4] = [
+    ("be", "is"),
+    ("have", "has"),
+    ("do", "does"),
+    ("go", "goes"),
+];
+
+/// Endings that take "es" instead of "s" in third person singular.
+static SIBILANT_ENDINGS: [&str; 6] = ["s", "x", "z", "ch", "sh", "o"];
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+/// Conjugates a verb in its base form into third person singular present, eg.
+/// "slash" -> "slashes", "parry" -> "parries", "have" -> "has".
+///
+/// Only the first word is conjugated so that phrasal verbs work as expected,
+/// eg. "lash out at" -> "lashes out at".
+pub fn third_person(verb: &str) -> String {
+    let verb = verb.trim();
+    let (head, tail) = match verb.find(' ') {
+        Some(idx) => verb.split_at(idx),
+        None => (verb, ""),
+    };
+    if head.is_empty() {
+        return String::new();
+    }
+    format!("{}{}", conjugate_word(head), tail)
+}
+
+fn conjugate_word(word: &str) -> String {
+    let lower = word.to_lowercase();
+
+    // Irregular verbs, keep the capitalization of the first letter
+    if let Some(&(_, conjugated)) = IRREGULAR_VERBS.iter().find(|&&(base, _)| base == lower) {
+        return match word.chars().next() {
+            Some(first) if first.is_uppercase() => capitalize(conjugated),
+            _ => conjugated.to_owned(),
+        };
+    }
+
+    // Consonant + y -> ies, eg. "parry" -> "parries"
+    if lower.ends_with('y') {
+        let before_y = lower.chars().rev().nth(1);
+        if let Some(letter) = before_y {
+            if !VOWELS.contains(&letter) {
+                return format!("{}ies", &word[..word.len() - 1]);
+            }
+        }
+    }
+
+    if SIBILANT_ENDINGS.iter().any(|ending| lower.ends_with(ending)) {
+        format!("{}es", word)
+    } else {
+        format!("{}s", word)
+    }
+}
@@ -0,0 +1,199 @@
+use std::collections::HashMap;
+
+/// Semantic role of a piece of narration, used by backends for styling.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Role {
+    /// Whoever performs the action.
+    Actor,
+    /// Whoever the action is performed on.
+    Target,
+    Weapon,
+    Damage,
+    Death,
+}
+
+impl Role {
+    fn from_tag(tag: &str) -> Option<Role> {
+        use self::Role::*;
+        match tag {
+            "actor" => Some(Actor),
+            "target" => Some(Target),
+            "weapon" => Some(Weapon),
+            "damage" => Some(Damage),
+            "death" => Some(Death),
+            _ => None,
+        }
+    }
+    fn tag(&self) -> &'static str {
+        use self::Role::*;
+        match *self {
+            Actor => "actor",
+            Target => "target",
+            Weapon => "weapon",
+            Damage => "damage",
+            Death => "death",
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Span {
+    Text(String),
+    Tagged(Role, Vec<Span>),
+}
+
+/// Narration as a tree of spans tagged with semantic roles.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Narration {
+    pub spans: Vec<Span>,
+}
+
+/// Turns a span tree into text.
+pub trait Backend {
+    /// Escapes untagged text.
+    fn escape(&self, text: &str) -> String {
+        text.to_owned()
+    }
+    /// Wraps already rendered contents of a span. `outer` are the roles of the
+    /// enclosing spans, innermost last.
+    fn wrap(&self, role: Role, contents: String, outer: &[Role]) -> String;
+}
+
+/// Discards all markup.
+pub struct PlainText;
+
+/// Colors the spans with ANSI escape codes for terminals.
+pub struct Ansi;
+
+/// Wraps the spans in `<span class="...">` elements, eg. `class="death"`.
+pub struct Html;
+
+impl Backend for PlainText {
+    fn wrap(&self, _role: Role, contents: String, _outer: &[Role]) -> String {
+        contents
+    }
+}
+
+impl Ansi {
+    fn code(role: Role) -> &'static str {
+        use self::Role::*;
+        match role {
+            Actor => "\x1b[1;36m",
+            Target => "\x1b[1;33m",
+            Weapon => "\x1b[35m",
+            Damage => "\x1b[31m",
+            Death => "\x1b[1;31m",
+        }
+    }
+}
+
+impl Backend for Ansi {
+    fn wrap(&self, role: Role, contents: String, outer: &[Role]) -> String {
+        // Reset after the span and restore the styles of enclosing spans
+        let restore: String = outer.iter().map(|&r| Ansi::code(r)).collect();
+        format!("{}{}\x1b[0m{}", Ansi::code(role), contents, restore)
+    }
+}
+
+impl Backend for Html {
+    fn escape(&self, text: &str) -> String {
+        text.replace('&', "&amp;")
+            .replace('<', "&lt;")
+            .replace('>', "&gt;")
+            .replace('"', "&quot;")
+    }
+    fn wrap(&self, role: Role, contents: String, _outer: &[Role]) -> String {
+        format!("<span class=\"{}\">{}</span>", role.tag(), contents)
+    }
+}
+
+impl Narration {
+    pub fn new(spans: Vec<Span>) -> Narration {
+        Narration { spans }
+    }
+    /// Fills a template where `{{name}}` is replaced with the value of the
+    /// variable and `{{#role}}...{{/role}}` tags the enclosed text with a role,
+    /// eg. `{{#actor}}{{name}}{{/actor}}`. Unknown variables are left empty.
+    pub fn from_template(template: &str, variables: &HashMap<&str, String>) -> Narration {
+        // Stack of open sections, the bottom one is the root
+        let mut stack: Vec<(Option<Role>, Vec<Span>)> = vec![(None, vec![])];
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            push_text(&mut stack, &rest[..start]);
+            let end = match rest[start..].find("}}") {
+                Some(end) => start + end,
+                None => {
+                    rest = &rest[start..];
+                    break;
+                }
+            };
+            let tag = rest[start + 2..end].trim();
+            if let Some(opened) = tag.strip_prefix('#') {
+                match Role::from_tag(opened) {
+                    Some(role) => stack.push((Some(role), vec![])),
+                    None => push_text(&mut stack, &rest[start..end + 2]),
+                }
+            } else if let Some(closed) = tag.strip_prefix('/') {
+                let closes_top = match stack.last() {
+                    Some(&(Some(role), _)) => Role::from_tag(closed) == Some(role),
+                    _ => false,
+                };
+                if closes_top {
+                    let (role, spans) = stack.pop().unwrap();
+                    push_span(&mut stack, Span::Tagged(role.unwrap(), spans));
+                }
+            } else if let Some(value) = variables.get(tag) {
+                push_text(&mut stack, value);
+            }
+            rest = &rest[end + 2..];
+        }
+        push_text(&mut stack, rest);
+
+        // Close unterminated sections
+        while stack.len() > 1 {
+            let (role, spans) = stack.pop().unwrap();
+            push_span(&mut stack, Span::Tagged(role.unwrap(), spans));
+        }
+        Narration::new(stack.pop().unwrap().1)
+    }
+    pub fn render<B: Backend>(&self, backend: &B) -> String {
+        render_spans(&self.spans, backend, &mut vec![])
+    }
+    /// Renders the narration without any markup.
+    pub fn to_plain(&self) -> String {
+        self.render(&PlainText)
+    }
+}
+
+fn push_text(stack: &mut [(Option<Role>, Vec<Span>)], text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    let spans = &mut stack.last_mut().unwrap().1;
+    // Merge adjacent text spans
+    if let Some(&mut Span::Text(ref mut previous)) = spans.last_mut() {
+        previous.push_str(text);
+        return;
+    }
+    spans.push(Span::Text(text.to_owned()));
+}
+
+fn push_span(stack: &mut [(Option<Role>, Vec<Span>)], span: Span) {
+    stack.last_mut().unwrap().1.push(span);
+}
+
+fn render_spans<B: Backend>(spans: &[Span], backend: &B, outer: &mut Vec<Role>) -> String {
+    let mut out = String::new();
+    for span in spans {
+        match *span {
+            Span::Text(ref text) => out += &backend.escape(text),
+            Span::Tagged(role, ref children) => {
+                outer.push(role);
+                let contents = render_spans(children, backend, outer);
+                outer.pop();
+                out += &backend.wrap(role, contents, outer);
+            }
+        }
+    }
+    out
+}
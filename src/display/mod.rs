@@ -0,0 +1,92 @@
+pub mod conjugation;
+pub mod markup;
+pub mod noun;
+#[cfg(test)]
+mod tests;
+
+pub use self::noun::{Countability, NounKind};
+
+pub const DEFINITE_ARTICLE: &str = "the";
+/// The verb used for weapons that do not declare their own.
+pub const DEFAULT_OFFENSIVE_VERB: &str = "bash";
+
+pub trait Display {
+    fn name(&self) -> String;
+    /// Proper nouns, eg. "Bob", never take an article. Names are common nouns by default.
+    fn noun_kind(&self) -> NounKind {
+        NounKind::Common
+    }
+    fn countability(&self) -> Countability {
+        Countability::Countable
+    }
+    fn default_article(&self) -> &str {
+        self.indefinite_article()
+    }
+    /// Returns "a", "an", "some" or nothing at all for proper nouns.
+    fn indefinite_article(&self) -> &str {
+        match (self.noun_kind(), self.countability()) {
+            (NounKind::Proper, _) => "",
+            (NounKind::Common, Countability::Mass) => "some",
+            (NounKind::Common, Countability::Countable) => {
+                noun::indefinite_article_for(&self.name())
+            }
+        }
+    }
+    /// Returns "the" or nothing at all for proper nouns.
+    fn definite_article(&self) -> &str {
+        match self.noun_kind() {
+            NounKind::Proper => "",
+            NounKind::Common => DEFINITE_ARTICLE,
+        }
+    }
+    /// The name with the definite article, eg. "the goblin" or "Bob".
+    fn definite_name(&self) -> String {
+        with_article(self.definite_article(), &self.name())
+    }
+    /// The name with the indefinite article, eg. "an orc", "some water" or "Bob".
+    fn indefinite_name(&self) -> String {
+        with_article(self.indefinite_article(), &self.name())
+    }
+    /// The plural form of the name. Proper and mass nouns are returned as is.
+    fn plural_name(&self) -> String {
+        match (self.noun_kind(), self.countability()) {
+            (NounKind::Common, Countability::Countable) => noun::pluralize(&self.name()),
+            _ => self.name(),
+        }
+    }
+    /// How many things the name refers to, eg. the number of potions in a stack.
+    fn quantity(&self) -> usize {
+        1
+    }
+    /// The name preceded by the quantity of the thing itself, eg. "three healing potions".
+    fn quantified_name(&self) -> String {
+        self.counted_name(self.quantity())
+    }
+    /// The name preceded by a quantity, eg. "a goblin" or "three goblins".
+    fn counted_name(&self, count: usize) -> String {
+        match (self.noun_kind(), self.countability(), count) {
+            (NounKind::Common, Countability::Countable, 1) => self.indefinite_name(),
+            (NounKind::Common, Countability::Countable, n) => {
+                format!("{} {}", noun::number_word(n), self.plural_name())
+            }
+            _ => self.indefinite_name(),
+        }
+    }
+}
+
+pub trait DisplayWeapon: Display {
+    fn display_offensive_action_1st(&self) -> String {
+        DEFAULT_OFFENSIVE_VERB.to_owned()
+    }
+    fn display_offensive_action_2nd(&self) -> String {
+        conjugation::third_person(&self.display_offensive_action_1st())
+    }
+}
+
+fn with_article(article: &str, name: &str) -> String {
+    if article.is_empty() {
+        name.to_owned()
+    } else {
+        format!("{} {}", article, name)
+    }
+}
@@ -0,0 +1,74 @@
+use inflector::Inflector;
+
+/// Whether a name refers to a specific individual or to a kind of thing.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NounKind {
+    /// Names of individuals, eg. "Bob". Never take an article.
+    Proper,
+    /// Names of kinds of things, eg. "goblin".
+    #[default]
+    Common,
+}
+
+/// Whether a common noun can be counted, eg. "goblin", or not, eg. "water".
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Countability {
+    #[default]
+    Countable,
+    Mass,
+}
+
+/// Words starting with a vowel letter that are pronounced with a consonant sound.
+static CONSONANT_SOUNDING_PREFIXES: [&str; 7] = ["uni", "use", "usu", "uti", "eu", "ewe", "one"];
+/// Words starting with a consonant letter that are pronounced with a vowel sound.
+static VOWEL_SOUNDING_PREFIXES: [&str; 4] = ["hour", "honest", "honor", "heir"];
+
+const VOWELS: [char; 5] = ['a', 'e', 'i', 'o', 'u'];
+
+static NUMBER_WORDS: [&str; 13] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+    "eleven", "twelve",
+];
+
+/// Returns "a" or "an" depending on how the word is most likely pronounced.
+pub fn indefinite_article_for(word: &str) -> &'static str {
+    let lower = word.trim().to_lowercase();
+    if VOWEL_SOUNDING_PREFIXES.iter().any(|p| lower.starts_with(p)) {
+        return "an";
+    }
+    if CONSONANT_SOUNDING_PREFIXES.iter().any(|p| lower.starts_with(p)) {
+        return "a";
+    }
+    match lower.chars().next() {
+        Some(letter) if VOWELS.contains(&letter) => "an",
+        _ => "a",
+    }
+}
+
+/// Pluralizes a possibly multi-word name, eg. "potion of healing" -> "potions of healing".
+pub fn pluralize(name: &str) -> String {
+    match name.find(" of ") {
+        Some(idx) => {
+            let (head, tail) = name.split_at(idx);
+            format!("{}{}", head.to_plural(), tail)
+        }
+        None => name.to_plural(),
+    }
+}
+
+/// Spells out small numbers, eg. 3 -> "three". Larger numbers are written with digits.
+pub fn number_word(n: usize) -> String {
+    match NUMBER_WORDS.get(n) {
+        Some(word) => (*word).to_owned(),
+        None => n.to_string(),
+    }
+}
+
+/// Uppercases the first letter of the text, eg. for the start of a sentence.
+pub fn capitalize(text: &str) -> String {
+    let mut chars = text.chars();
+    match chars.next() {
+        None => String::new(),
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+    }
+}
@@ -0,0 +1,175 @@
+use display::conjugation::third_person;
+use item::*;
+use character::*;
+use combat::*;
+use monster::*;
+use display::Display;
+use display::DisplayWeapon;
+
+#[test]
+fn regular_verbs_are_conjugated() {
+    assert_eq!(third_person("hit"), "hits");
+    assert_eq!(third_person("stab"), "stabs");
+    assert_eq!(third_person("cleave"), "cleaves");
+    assert_eq!(third_person("slay"), "slays");
+}
+
+#[test]
+fn sibilant_and_y_endings_are_conjugated() {
+    assert_eq!(third_person("slash"), "slashes");
+    assert_eq!(third_person("bash"), "bashes");
+    assert_eq!(third_person("punch"), "punches");
+    assert_eq!(third_person("toss"), "tosses");
+    assert_eq!(third_person("parry"), "parries");
+}
+
+#[test]
+fn irregular_verbs_are_conjugated() {
+    assert_eq!(third_person("have"), "has");
+    assert_eq!(third_person("go"), "goes");
+    assert_eq!(third_person("lash out at"), "lashes out at");
+}
+
+#[test]
+fn weapon_uses_declared_verb() {
+    let sword = equipment("Sword", 1, Slot::Hand, vec![]).verb("slash").build();
+    let club = equipment("Club", 1, Slot::Hand, vec![]).build();
+
+    assert_eq!(sword.display_offensive_action_1st(), "slash");
+    assert_eq!(sword.display_offensive_action_2nd(), "slashes");
+    assert_eq!(club.display_offensive_action_1st(), "bash");
+    assert_eq!(club.display_offensive_action_2nd(), "bashes");
+}
+
+#[test]
+fn indefinite_article_follows_pronunciation() {
+    use display::noun::indefinite_article_for;
+    assert_eq!(indefinite_article_for("orc"), "an");
+    assert_eq!(indefinite_article_for("goblin"), "a");
+    assert_eq!(indefinite_article_for("yeti"), "a");
+    assert_eq!(indefinite_article_for("hour"), "an");
+    assert_eq!(indefinite_article_for("honest merchant"), "an");
+    assert_eq!(indefinite_article_for("unicorn"), "a");
+    assert_eq!(indefinite_article_for("one-eyed troll"), "a");
+}
+
+#[test]
+fn proper_nouns_take_no_article() {
+    let character = CharacterBuilder::new(1, CharacterAttributes::default()).named("Bob").build();
+    let goblin = MonsterBuilder::new("goblin", 1, 1).spawn();
+    let boss = MonsterBuilder::new("Gzolneb", 1, 1).proper_noun().spawn();
+
+    assert_eq!(character.definite_name(), "Bob");
+    assert_eq!(character.indefinite_name(), "Bob");
+    assert_eq!(goblin.definite_name(), "the goblin");
+    assert_eq!(goblin.indefinite_name(), "a goblin");
+    assert_eq!(boss.definite_name(), "Gzolneb");
+}
+
+#[test]
+fn names_are_counted_and_pluralized() {
+    let goblin = MonsterBuilder::new("goblin", 1, 1).spawn();
+    let potion = consumable("potion of healing", 1, vec![]).build();
+    let water = consumable("water", 1, vec![]).mass_noun().build();
+
+    assert_eq!(goblin.counted_name(1), "a goblin");
+    assert_eq!(goblin.counted_name(3), "three goblins");
+    assert_eq!(goblin.counted_name(20), "20 goblins");
+    assert_eq!(potion.counted_name(2), "two potions of healing");
+    assert_eq!(water.indefinite_name(), "some water");
+    assert_eq!(water.counted_name(2), "some water");
+}
+
+#[test]
+fn narration_uses_articles() {
+    let character = Character::default();
+    let goblin = MonsterBuilder::new("goblin", 1, 1).spawn();
+    let boss = MonsterBuilder::new("Gzolneb", 1, 1).proper_noun().spawn();
+
+    let goblin_log = match Combat::new(&character, &goblin).results {
+        Results::Begin { log, .. } => log,
+        _ => unreachable!(),
+    };
+    let boss_log = match Combat::new(&character, &boss).results {
+        Results::Begin { log, .. } => log,
+        _ => unreachable!(),
+    };
+
+    assert_eq!(goblin_log, "The goblin notices you and attacks.");
+    assert_eq!(boss_log, "Gzolneb notices you and attacks.");
+}
+
+#[test]
+fn template_is_parsed_into_spans() {
+    use display::markup::*;
+    let variables = hashmap!("name" => "goblin".to_owned(), "weapon" => "club".to_owned());
+
+    let narration = Narration::from_template(
+        "The {{#actor}}{{name}}{{/actor}} hits you with a {{#weapon}}{{weapon}}{{/weapon}}.",
+        &variables,
+    );
+
+    assert_eq!(
+        narration.spans,
+        vec![
+            Span::Text("The ".to_owned()),
+            Span::Tagged(Role::Actor, vec![Span::Text("goblin".to_owned())]),
+            Span::Text(" hits you with a ".to_owned()),
+            Span::Tagged(Role::Weapon, vec![Span::Text("club".to_owned())]),
+            Span::Text(".".to_owned()),
+        ]
+    );
+}
+
+#[test]
+fn narration_renders_to_backends() {
+    use display::markup::*;
+    let narration = Narration::new(vec![
+        Span::Tagged(Role::Actor, vec![Span::Text("Bob".to_owned())]),
+        Span::Text(" & ".to_owned()),
+        Span::Tagged(
+            Role::Death,
+            vec![
+                Span::Text("the ".to_owned()),
+                Span::Tagged(Role::Target, vec![Span::Text("goblin".to_owned())]),
+            ],
+        ),
+    ]);
+
+    assert_eq!(narration.to_plain(), "Bob & the goblin");
+    assert_eq!(
+        narration.render(&Html),
+        "<span class=\"actor\">Bob</span> &amp; <span class=\"death\">the \
+         <span class=\"target\">goblin</span></span>"
+    );
+    assert_eq!(
+        narration.render(&Ansi),
+        "\x1b[1;36mBob\x1b[0m & \x1b[1;31mthe \x1b[1;33mgoblin\x1b[0m\x1b[1;31m\x1b[0m"
+    );
+}
+
+#[test]
+fn combat_narration_is_tagged() {
+    use display::markup::*;
+    let mut character = Character::default();
+    let mut goblin = MonsterBuilder::new("goblin", 1, 1).spawn();
+
+    let mut combat = Combat::new(&character, &goblin);
+    let narration = match combat.results {
+        Results::Begin { ref narration, .. } => narration.clone(),
+        _ => unreachable!(),
+    };
+    combat.apply_round(&mut character, &mut goblin);
+
+    assert_eq!(
+        narration.spans[0],
+        Span::Tagged(Role::Actor, vec![Span::Text("The goblin".to_owned())])
+    );
+    match combat.results {
+        Results::End { ref log, ref narration, .. } => {
+            assert!(!log.contains("{{"));
+            assert!(narration.render(&Html).contains("<span class=\"death\">"));
+        }
+        _ => unreachable!(),
+    }
+}
@@ -26,8 +26,13 @@ pub struct Generator<'a> {
     /// no. of rooms in an area. Rooms are for encounters with monsters, treasures and altars.
     num_main_rooms_in_area: Range,
     force_first_room_empty: bool,
+    /// Lower bound for room difficulty, raised by the level of the character.
+    difficulty_floor: f32,
 }
 
+/// Characters of this level or higher face the hardest content in every room.
+pub const LEVEL_FOR_MAX_DIFFICULTY: u32 = 10;
+
 // TOOD: reimplement with lifetime subtyping so that no copies of Keywords have
 // to be made
 impl<'a> Generator<'a> {
@@ -54,8 +59,16 @@ impl<'a> Generator<'a> {
             num_areas_in_arch: num_areas_in_arch,
             num_main_rooms_in_area: num_main_rooms_in_area,
             force_first_room_empty,
+            difficulty_floor: 0.,
         }
     }
+    /// Targets the difficulty of the dungeon at a character of the given level.
+    /// Level 1 spans the whole difficulty range, higher levels skip the easiest content.
+    pub fn for_level(mut self, level: u32) -> Generator<'a> {
+        let level = level.clamp(1, LEVEL_FOR_MAX_DIFFICULTY);
+        self.difficulty_floor = (level - 1) as f32 / (LEVEL_FOR_MAX_DIFFICULTY - 1) as f32;
+        self
+    }
 
     pub fn generate(&'a self, seed: &'a [usize]) -> Dungeon {
         // Rename binding for conciseness
@@ -99,8 +112,10 @@ impl<'a> Generator<'a> {
 
         let mut arches = Vec::with_capacity(g.arch_count);
         for arch_idx in 0..g.arch_count {
-            let difficulty_min = arch_idx as f32 / g.arch_count as f32;
-            let difficulty_max = (arch_idx + 1) as f32 / g.arch_count as f32;
+            let span = 1. - g.difficulty_floor;
+            let difficulty_min = g.difficulty_floor + span * arch_idx as f32 / g.arch_count as f32;
+            let difficulty_max =
+                g.difficulty_floor + span * (arch_idx + 1) as f32 / g.arch_count as f32;
 
             let arch_keywords = rng.choose_many(g.arch_keyword_count, keyword_pool);
 
@@ -177,7 +192,7 @@ impl<'a> Generator<'a> {
     fn generate_room(&self, keywords: &[&Keyword], difficulty: f32, rng: &mut StdRng) -> Room {
         Room::new(
             keywords[0],
-            Some(self.generate_monster(difficulty, keywords, rng)),
+            Some(self.generate_monster(difficulty, keywords, rng).spawn()),
         )
     }
     fn generate_monster(
@@ -153,3 +153,28 @@ fn difficulty_is_normalized() {
     assert_eq!(goblin.normalized_difficulty(), 0.1);
     assert_eq!(demon.normalized_difficulty(), 1.);
 }
+
+#[test]
+fn character_level_raises_difficulty() {
+    let monster_pool = vec![
+        MonsterBuilder::new("goblin", 1, 3).difficulty(1).spawn(),
+        MonsterBuilder::new("demon", 15, 40).difficulty(10).spawn(),
+    ];
+    let g = Generator::new(monster_pool.as_slice(),
+                           TEMPLATE_MONSTER_POOL.as_slice(),
+                           THEME_KEYWORD_POOL.as_slice(),
+                           8,
+                           5,
+                           3,
+                           2,
+                           Range::new(2, 1),
+                           Range::new(3, 1),
+                           false)
+        .for_level(LEVEL_FOR_MAX_DIFFICULTY);
+
+    let dungeon = g.generate(&SEED);
+
+    for room in &dungeon.rooms {
+        assert_eq!(room.monster.as_ref().unwrap().name(), "demon");
+    }
+}
@@ -0,0 +1,36 @@
+use serde::{Deserialize, Deserializer};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(1);
+
+/// Globally unique identifier of an item, a monster or a character. Unlike
+/// positions in containers, the identifier never changes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+pub struct EntityId(usize);
+
+impl EntityId {
+    /// Returns an identifier that has not been used before.
+    pub fn new() -> EntityId {
+        EntityId(NEXT_ID.fetch_add(1, Ordering::SeqCst))
+    }
+}
+
+impl Default for EntityId {
+    fn default() -> EntityId {
+        EntityId::new()
+    }
+}
+
+/// Loading an identifier makes sure that new identifiers are not handed out again.
+impl<'de> Deserialize<'de> for EntityId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<EntityId, D::Error> {
+        let id = usize::deserialize(deserializer)?;
+        NEXT_ID.fetch_max(id.saturating_add(1), Ordering::SeqCst);
+        Ok(EntityId(id))
+    }
+}
+
+/// Something that exists in the game world on its own.
+pub trait Entity {
+    fn id(&self) -> EntityId;
+}
@@ -1,4 +1,7 @@
 use super::*;
+use entity::EntityId;
+use character::Inventory;
+use std::cmp::{max, min};
 
 pub fn consumable<IEVec: AsRef<Vec<ItemEffect>>>(
     name: &str,
@@ -7,11 +10,19 @@ pub fn consumable<IEVec: AsRef<Vec<ItemEffect>>>(
 ) -> ConsumableBuilder {
     ConsumableBuilder {
         consumable: Consumable {
+            id: EntityId::new(),
             size,
             effects: effects.as_ref().clone(),
             name: name.to_owned(),
             max_uses: 1,
             uses: 1,
+            countability: Countability::Countable,
+            footprint: None,
+            weight: 0,
+            tags: vec![],
+            stack_limit: 1,
+            count: 1,
+            teaches: vec![],
         },
     }
 }
@@ -24,6 +35,7 @@ pub fn equipment<S: AsRef<Slot>, IEVec: AsRef<Vec<ItemEffect>>>(
 ) -> EquipmentBuilder {
     EquipmentBuilder {
         equipment: Equipment {
+            id: EntityId::new(),
             slot: slot.as_ref().clone(),
             name: name.to_owned(),
             effects: effects.as_ref().clone(),
@@ -31,6 +43,30 @@ pub fn equipment<S: AsRef<Slot>, IEVec: AsRef<Vec<ItemEffect>>>(
             damage: 0,
             prefix: None,
             suffix: None,
+            extra_slots: vec![],
+            footprint: None,
+            weight: 0,
+            tags: vec![],
+            requirements: vec![],
+            verb: None,
+            teaches: vec![],
+            set: None,
+        },
+    }
+}
+
+/// A container that holds `capacity` size-units and takes `size` space itself.
+pub fn container(name: &str, size: usize, capacity: usize) -> ContainerBuilder {
+    ContainerBuilder {
+        container: Container {
+            id: EntityId::new(),
+            name: name.to_owned(),
+            size,
+            weight: 0,
+            footprint: None,
+            accepts: vec![],
+            tags: vec![],
+            contents: Inventory::new(capacity),
         },
     }
 }
@@ -43,15 +79,59 @@ pub struct EquipmentBuilder {
     equipment: Equipment,
 }
 
+pub struct ContainerBuilder {
+    container: Container,
+}
+
 impl ConsumableBuilder {
+    /// Builds a new consumable with its own identifier.
     pub fn build(&self) -> Consumable {
-        self.consumable.clone()
+        let mut consumable = self.consumable.clone();
+        consumable.id = EntityId::new();
+        consumable.count = min(consumable.count, consumable.stack_limit);
+        consumable
     }
     pub fn uses(mut self, uses: usize) -> ConsumableBuilder {
         self.consumable.uses = uses;
         self.consumable.max_uses = uses;
         self
     }
+    /// Marks the name as a mass noun, eg. "water", that is not counted.
+    pub fn mass_noun(mut self) -> ConsumableBuilder {
+        self.consumable.countability = Countability::Mass;
+        self
+    }
+    /// Sets the shape in a grid inventory. The size becomes the area of the footprint.
+    pub fn footprint(mut self, width: usize, height: usize) -> ConsumableBuilder {
+        self.consumable.footprint = Some(Footprint::new(width, height));
+        self.consumable.size = width * height;
+        self
+    }
+    /// Sets the weight of a single consumable.
+    pub fn weight(mut self, weight: u32) -> ConsumableBuilder {
+        self.consumable.weight = weight;
+        self
+    }
+    /// Adds a tag, eg. "arrow", that containers use to decide what they accept.
+    pub fn tagged<K: Into<Keyword>>(mut self, tag: K) -> ConsumableBuilder {
+        self.consumable.tags.push(tag.into());
+        self
+    }
+    /// Lets the consumable teach the named skill.
+    pub fn teaches(mut self, skill: &str) -> ConsumableBuilder {
+        self.consumable.teaches.push(skill.to_owned());
+        self
+    }
+    /// Allows up to `limit` identical consumables to share one space in an inventory.
+    pub fn stack_limit(mut self, limit: usize) -> ConsumableBuilder {
+        self.consumable.stack_limit = max(limit, 1);
+        self
+    }
+    /// Sets the number of consumables in the stack, at most the stack limit.
+    pub fn count(mut self, count: usize) -> ConsumableBuilder {
+        self.consumable.count = max(count, 1);
+        self
+    }
     pub fn remaining_uses(mut self, uses: usize, max_uses: usize) -> ConsumableBuilder {
         self.consumable.uses = uses;
         self.consumable.max_uses = max_uses;
@@ -60,8 +140,11 @@ impl ConsumableBuilder {
 }
 
 impl EquipmentBuilder {
+    /// Builds a new equipment with its own identifier.
     pub fn build(&self) -> Equipment {
-        self.equipment.clone()
+        let mut equipment = self.equipment.clone();
+        equipment.id = EntityId::new();
+        equipment
     }
     pub fn damage(mut self, damage: i32) -> EquipmentBuilder {
         self.equipment.damage = damage;
@@ -75,4 +158,81 @@ impl EquipmentBuilder {
         self.equipment.suffix = Some(suffix.as_ref().clone());
         self
     }
+    /// Sets the shape in a grid inventory. The size becomes the area of the footprint.
+    pub fn footprint(mut self, width: usize, height: usize) -> EquipmentBuilder {
+        self.equipment.footprint = Some(Footprint::new(width, height));
+        self.equipment.size = width * height;
+        self
+    }
+    pub fn weight(mut self, weight: u32) -> EquipmentBuilder {
+        self.equipment.weight = weight;
+        self
+    }
+    /// Adds a tag that containers use to decide what they accept.
+    pub fn tagged<K: Into<Keyword>>(mut self, tag: K) -> EquipmentBuilder {
+        self.equipment.tags.push(tag.into());
+        self
+    }
+    /// Makes the equipment a piece of the named set.
+    pub fn part_of(mut self, set: &str) -> EquipmentBuilder {
+        self.equipment.set = Some(set.to_owned());
+        self
+    }
+    /// Lets the equipment teach the named skill.
+    pub fn teaches(mut self, skill: &str) -> EquipmentBuilder {
+        self.equipment.teaches.push(skill.to_owned());
+        self
+    }
+    /// Makes the equipment occupy another slot in addition to its primary slot.
+    pub fn also_occupies<S: AsRef<Slot>>(mut self, slot: S) -> EquipmentBuilder {
+        self.equipment.extra_slots.push(*slot.as_ref());
+        self
+    }
+    /// Makes the equipment occupy both hands.
+    pub fn two_handed(self) -> EquipmentBuilder {
+        self.also_occupies(Slot::Hand)
+    }
+    /// Requires the character to have at least `minimum` of the base attribute to equip the item.
+    pub fn requires<A: AsRef<Attribute>>(mut self, attribute: A, minimum: i32) -> EquipmentBuilder {
+        self.equipment.requirements.push(Requirement {
+            attribute: *attribute.as_ref(),
+            minimum,
+        });
+        self
+    }
+    /// Sets the verb used when hitting something with the equipment, eg. "slash".
+    pub fn verb(mut self, verb: &str) -> EquipmentBuilder {
+        self.equipment.verb = Some(verb.to_owned());
+        self
+    }
+}
+
+impl ContainerBuilder {
+    /// Builds a new container with its own identifier.
+    pub fn build(&self) -> Container {
+        let mut container = self.container.clone();
+        container.id = EntityId::new();
+        container
+    }
+    /// Only accepts items that have the tag, or any of the tags if called several times.
+    pub fn accepts<K: Into<Keyword>>(mut self, tag: K) -> ContainerBuilder {
+        self.container.accepts.push(tag.into());
+        self
+    }
+    /// Adds a tag that other containers use to decide what they accept.
+    pub fn tagged<K: Into<Keyword>>(mut self, tag: K) -> ContainerBuilder {
+        self.container.tags.push(tag.into());
+        self
+    }
+    /// Sets the weight of the empty container.
+    pub fn weight(mut self, weight: u32) -> ContainerBuilder {
+        self.container.weight = weight;
+        self
+    }
+    /// Sets the shape in a grid inventory. The size becomes the area of the footprint.
+    pub fn footprint(mut self, width: usize, height: usize) -> ContainerBuilder {
+        self.container.footprint = Some(Footprint::new(width, height));
+        self.container.size = width * height;
+        self
+    }
 }
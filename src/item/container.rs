@@ -0,0 +1,114 @@
+use super::*;
+use character::Inventory;
+
+/// An item that holds other items, eg. a pouch or a quiver. Takes `size`
+/// space in its parent regardless of its contents.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Container {
+    #[serde(default)]
+    pub(super) id: EntityId,
+    pub(super) name: String,
+    pub(super) size: usize,
+    /// Weight of the empty container.
+    #[serde(default)]
+    pub(super) weight: u32,
+    #[serde(default)]
+    pub(super) footprint: Option<Footprint>,
+    /// Only items with any of the tags are accepted. Anything is accepted if empty.
+    #[serde(default)]
+    pub(super) accepts: Vec<Keyword>,
+    #[serde(default)]
+    pub(super) tags: Vec<Keyword>,
+    pub(super) contents: Inventory,
+}
+
+impl Container {
+    /// Returns a copy of the container and its contents as new entities.
+    pub fn spawn(&self) -> Container {
+        let mut container = self.clone();
+        container.id = EntityId::new();
+        container.contents = Inventory::new(self.contents.capacity());
+        for entry in self.contents.iter() {
+            container.contents.put_at(entry.item.spawn(), entry.position);
+        }
+        container
+    }
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    /// Weight of the container and its contents.
+    pub fn weight(&self) -> u32 {
+        self.weight + self.contents.iter().map(|entry| entry.item.weight()).sum::<u32>()
+    }
+    pub fn tags(&self) -> &[Keyword] {
+        self.tags.as_slice()
+    }
+    /// Returns true if the item is allowed in the container. Does not check for room.
+    pub fn accepts(&self, item: &Item) -> bool {
+        self.accepts.is_empty() || self.accepts.iter().any(|tag| item.tags().contains(tag))
+    }
+    pub fn contents(&self) -> &Inventory {
+        &self.contents
+    }
+    pub fn contents_mut(&mut self) -> &mut Inventory {
+        &mut self.contents
+    }
+}
+
+impl Entity for Container {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
+impl Display for Container {
+    fn name(&self) -> String {
+        self.name.clone()
+    }
+}
+
+/// Items in the container are accessed with their positions in the contents.
+impl HoldsItems for Container {
+    fn capacity(&self) -> usize {
+        self.contents.capacity()
+    }
+    /// Returns None if the container does not accept the item or has no room.
+    fn put(&mut self, item: Item) -> Option<usize> {
+        if !self.accepts(&item) {
+            return None;
+        }
+        self.contents.put(item)
+    }
+    fn take(&mut self, pos: i32) -> Option<Item> {
+        self.contents.take(pos)
+    }
+    fn get(&self, pos: i32) -> Option<&Item> {
+        self.contents.get(pos)
+    }
+    fn get_mut(&mut self, pos: i32) -> Option<&mut Item> {
+        self.contents.get_mut(pos)
+    }
+    fn get_clone(&self, pos: i32) -> Option<Item> {
+        self.contents.get_clone(pos)
+    }
+    fn holds_id(&self, id: usize) -> bool {
+        self.contents.holds_id(id)
+    }
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        self.contents.iter()
+    }
+}
+
+/// Reasons why an item could not be moved between nested containers.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ContainerError {
+    /// There is no item at the path.
+    NotFound,
+    /// The destination is not a container.
+    NotAContainer,
+    /// The item would be put inside itself or a container within it.
+    IntoItself,
+    /// The destination does not accept the item.
+    Rejected,
+    NoRoom,
+}
@@ -1,24 +1,36 @@
 mod affix;
 mod builder;
+mod container;
+mod set;
 #[cfg(test)]
 mod tests;
 
 pub use self::builder::*;
 pub use self::affix::*;
+pub use self::container::*;
+pub use self::set::*;
 
-use super::{Display, DisplayWeapon};
+use super::{Countability, Display, DisplayWeapon};
+use display::DEFAULT_OFFENSIVE_VERB;
 use std::convert::{From, Into};
 use try_from::*;
-use character::Attribute;
+use character::{Attribute, Condition, EquipmentStore, Modifier, ModifierValue};
+use std::cmp::{max, min};
+use theme::Keyword;
+use entity::{Entity, EntityId};
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub enum Item {
     Consumable(Consumable),
     Equipment(Equipment),
+    Container(Container),
 }
 
-#[derive(Clone)]
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(from = "ConsumableData")]
 pub struct Consumable {
+    #[serde(default)]
+    id: EntityId,
     /// Amount of space taken while in an inventory.
     size: usize,
     /// Implicit effects of the consumable type.
@@ -26,10 +38,83 @@ pub struct Consumable {
     name: String,
     max_uses: usize,
     uses: usize,
+    /// Mass nouns, eg. "water", are not counted.
+    countability: Countability,
+    /// Shape in a grid inventory, a strip of `size` cells if not set.
+    #[serde(default)]
+    footprint: Option<Footprint>,
+    /// Weight of a single consumable.
+    #[serde(default)]
+    weight: u32,
+    /// Used by containers to decide what they accept, eg. "arrow".
+    #[serde(default)]
+    tags: Vec<Keyword>,
+    /// Maximum number of identical consumables in one stack. 1 for consumables that do not stack.
+    #[serde(default = "default_stack_size")]
+    stack_limit: usize,
+    /// Number of identical consumables in this stack. The stack takes `size` space regardless.
+    #[serde(default = "default_stack_size")]
+    count: usize,
+    /// Names of the skills learned by reading or using the consumable, eg. a tome.
+    #[serde(default)]
+    teaches: Vec<String>,
 }
 
-#[derive(Serialize, Deserialize, Debug, Clone)]
+fn default_stack_size() -> usize {
+    1
+}
+
+/// Deserialized form of `Consumable`, the count is clamped to the stack limit
+/// when converted so that stacks are never empty or overfull.
+#[derive(Deserialize)]
+struct ConsumableData {
+    #[serde(default)]
+    id: EntityId,
+    size: usize,
+    effects: Vec<ItemEffect>,
+    name: String,
+    max_uses: usize,
+    uses: usize,
+    countability: Countability,
+    #[serde(default)]
+    footprint: Option<Footprint>,
+    #[serde(default)]
+    weight: u32,
+    #[serde(default)]
+    tags: Vec<Keyword>,
+    #[serde(default = "default_stack_size")]
+    stack_limit: usize,
+    #[serde(default = "default_stack_size")]
+    count: usize,
+    #[serde(default)]
+    teaches: Vec<String>,
+}
+
+impl From<ConsumableData> for Consumable {
+    fn from(data: ConsumableData) -> Consumable {
+        let stack_limit = max(data.stack_limit, 1);
+        Consumable {
+            id: data.id,
+            size: data.size,
+            effects: data.effects,
+            name: data.name,
+            max_uses: data.max_uses,
+            uses: data.uses,
+            countability: data.countability,
+            footprint: data.footprint,
+            weight: data.weight,
+            tags: data.tags,
+            stack_limit,
+            count: data.count.clamp(1, stack_limit),
+            teaches: data.teaches,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct Equipment {
+    #[serde(default)]
+    id: EntityId,
     slot: Slot,
     name: String,
     /// Implicit effects of the equipment type.
@@ -39,6 +124,29 @@ pub struct Equipment {
     damage: i32,
     prefix: Option<Prefix>,
     suffix: Option<Suffix>,
+    /// Slots occupied in addition to `slot`, eg. the other hand for two-handed weapons.
+    #[serde(default)]
+    extra_slots: Vec<Slot>,
+    /// Shape in a grid inventory, a strip of `size` cells if not set.
+    #[serde(default)]
+    footprint: Option<Footprint>,
+    #[serde(default)]
+    weight: u32,
+    /// Used by containers to decide what they accept.
+    #[serde(default)]
+    tags: Vec<Keyword>,
+    /// Minimum base attributes required to equip the item.
+    #[serde(default)]
+    requirements: Vec<Requirement>,
+    /// The verb used when hitting something with this item, eg. "slash". Uses the default verb if not set.
+    #[serde(default)]
+    verb: Option<String>,
+    /// Names of the skills the equipment teaches.
+    #[serde(default)]
+    teaches: Vec<String>,
+    /// Name of the set the equipment is a piece of, see `EquipmentSetLibrary`.
+    #[serde(default)]
+    set: Option<String>,
 }
 
 impl Display for Equipment {
@@ -57,12 +165,44 @@ impl Display for Equipment {
     }
 }
 
-impl DisplayWeapon for Equipment {}
+impl DisplayWeapon for Equipment {
+    fn display_offensive_action_1st(&self) -> String {
+        match self.verb {
+            Some(ref verb) => verb.clone(),
+            None => DEFAULT_OFFENSIVE_VERB.to_owned(),
+        }
+    }
+}
 
 impl Equipment {
+    /// Returns a copy of the equipment as a new entity with its own identifier.
+    pub fn spawn(&self) -> Equipment {
+        let mut equipment = self.clone();
+        equipment.id = EntityId::new();
+        equipment
+    }
+    /// Name of the set the equipment is a piece of.
+    pub fn set(&self) -> Option<&str> {
+        self.set.as_deref()
+    }
+    /// The name followed by the progress of its set, eg. "Crown, Iahu's
+    /// Regalia (2/4)". Only the name if the equipment is not a piece of a set.
+    pub fn display_with_set(&self, worn: &EquipmentStore, sets: &EquipmentSetLibrary) -> String {
+        match worn.set_progress(self, sets) {
+            Some(progress) => format!("{}, {}", self.name(), progress),
+            None => self.name(),
+        }
+    }
+    /// The primary slot of the equipment.
     pub fn slot(&self) -> &Slot {
         &self.slot
     }
+    /// All slots occupied by the equipment, the primary slot first.
+    pub fn slots(&self) -> Vec<Slot> {
+        let mut slots = vec![self.slot];
+        slots.extend(self.extra_slots.iter().cloned());
+        slots
+    }
     pub fn effects(&self) -> Vec<ItemEffect> {
         let mut all_effects = self.effects.clone();
         if let Some(ref prefix) = self.prefix {
@@ -80,24 +220,185 @@ impl Equipment {
     pub fn size(&self) -> usize {
         self.size
     }
+    pub fn weight(&self) -> u32 {
+        self.weight
+    }
+    pub fn requirements(&self) -> &[Requirement] {
+        self.requirements.as_slice()
+    }
     pub fn damage(&self) -> i32 {
         self.damage
     }
 }
 
+impl Consumable {
+    /// Returns a copy of the consumable as a new entity with its own identifier.
+    pub fn spawn(&self) -> Consumable {
+        let mut consumable = self.clone();
+        consumable.id = EntityId::new();
+        consumable
+    }
+    pub fn size(&self) -> usize {
+        self.size
+    }
+    pub fn count(&self) -> usize {
+        self.count
+    }
+    pub fn effects(&self) -> &[ItemEffect] {
+        self.effects.as_slice()
+    }
+    /// Uses up one use of the topmost consumable in the stack. Returns false
+    /// if it was the last use of the last consumable, ie. nothing is left.
+    pub fn use_once(&mut self) -> bool {
+        if self.uses > 1 {
+            self.uses -= 1;
+        } else if self.count > 1 {
+            self.count -= 1;
+            self.uses = self.max_uses;
+        } else {
+            return false;
+        }
+        true
+    }
+    /// Weight of the whole stack.
+    pub fn weight(&self) -> u32 {
+        self.weight * self.count as u32
+    }
+    pub fn stack_limit(&self) -> usize {
+        self.stack_limit
+    }
+    /// Returns true if the consumables are identical apart from their counts and identifiers.
+    pub fn stacks_with(&self, other: &Consumable) -> bool {
+        let mut other = other.clone();
+        other.count = self.count;
+        other.id = self.id;
+        self.stack_limit > 1 && *self == other
+    }
+    /// Moves as many consumables from the other stack into this one as fit.
+    /// Returns what is left of the other stack, or the whole stack if they do not stack.
+    pub fn merge(&mut self, mut other: Consumable) -> Option<Consumable> {
+        if !self.stacks_with(&other) {
+            return Some(other);
+        }
+        let moved = min(other.count, self.stack_limit.saturating_sub(self.count));
+        self.count += moved;
+        other.count -= moved;
+        if other.count > 0 {
+            Some(other)
+        } else {
+            None
+        }
+    }
+    /// Takes some of the consumables into a new stack. Returns None unless
+    /// the amount is less than the count, ie. at least one is left.
+    pub fn split(&mut self, amount: usize) -> Option<Consumable> {
+        if amount == 0 || amount >= self.count {
+            return None;
+        }
+        self.count -= amount;
+        let mut split = self.clone();
+        split.count = amount;
+        split.id = EntityId::new();
+        Some(split)
+    }
+}
+
+impl Entity for Consumable {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
+impl Entity for Equipment {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
+impl Entity for Item {
+    fn id(&self) -> EntityId {
+        match *self {
+            Item::Equipment(ref e) => e.id(),
+            Item::Consumable(ref c) => c.id(),
+            Item::Container(ref c) => c.id(),
+        }
+    }
+}
+
 impl Display for Consumable {
     fn name(&self) -> String {
         self.name.clone()
     }
+    fn countability(&self) -> Countability {
+        self.countability
+    }
+    fn quantity(&self) -> usize {
+        self.count
+    }
+}
+
+/// The variants of `Item` without their data, used for filtering.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub enum ItemKind {
+    Consumable,
+    Equipment,
+    Container,
 }
 
 impl Item {
+    /// Returns a copy of the item as a new entity. Contents of containers are copied too.
+    pub fn spawn(&self) -> Item {
+        match *self {
+            Item::Equipment(ref e) => Item::Equipment(e.spawn()),
+            Item::Consumable(ref c) => Item::Consumable(c.spawn()),
+            Item::Container(ref c) => Item::Container(c.spawn()),
+        }
+    }
+    pub fn kind(&self) -> ItemKind {
+        match *self {
+            Item::Consumable(_) => ItemKind::Consumable,
+            Item::Equipment(_) => ItemKind::Equipment,
+            Item::Container(_) => ItemKind::Container,
+        }
+    }
+    pub fn weight(&self) -> u32 {
+        match *self {
+            Item::Equipment(ref e) => e.weight(),
+            Item::Consumable(ref c) => c.weight(),
+            Item::Container(ref c) => c.weight(),
+        }
+    }
+    pub fn tags(&self) -> &[Keyword] {
+        match *self {
+            Item::Equipment(ref e) => e.tags.as_slice(),
+            Item::Consumable(ref c) => c.tags.as_slice(),
+            Item::Container(ref c) => c.tags(),
+        }
+    }
+    /// Names of the skills that can be learned from the item.
+    pub fn teaches(&self) -> &[String] {
+        match *self {
+            Item::Equipment(ref e) => e.teaches.as_slice(),
+            Item::Consumable(ref c) => c.teaches.as_slice(),
+            Item::Container(_) => &[],
+        }
+    }
+    /// Shape of the item in a grid inventory.
+    pub fn footprint(&self) -> Footprint {
+        let footprint = match *self {
+            Item::Equipment(ref e) => e.footprint,
+            Item::Consumable(ref c) => c.footprint,
+            Item::Container(ref c) => c.footprint,
+        };
+        footprint.unwrap_or_else(|| Footprint::new(self.size(), 1))
+    }
     pub fn size(&self) -> usize {
         match *self {
             Item::Equipment(ref e) => {
                 e.size()
             },
             Item::Consumable(ref c) => c.size,
+            Item::Container(ref c) => c.size(),
         }
     }
 }
@@ -108,6 +409,21 @@ impl Display for Item {
             Item::Equipment(ref e) =>
                 e.name(),
             Item::Consumable(ref c) => c.name.clone(),
+            Item::Container(ref c) => c.name(),
+        }
+    }
+    fn countability(&self) -> Countability {
+        match *self {
+            Item::Equipment(ref e) => e.countability(),
+            Item::Consumable(ref c) => c.countability(),
+            Item::Container(ref c) => c.countability(),
+        }
+    }
+    fn quantity(&self) -> usize {
+        match *self {
+            Item::Equipment(ref e) => e.quantity(),
+            Item::Consumable(ref c) => c.quantity(),
+            Item::Container(ref c) => c.quantity(),
         }
     }
 }
@@ -125,13 +441,94 @@ pub trait HoldsItems {
     fn get_mut(&mut self, id: i32) -> Option<&mut Item>;
     fn get_clone(&self, pos: i32) -> Option<Item>;
     fn holds_id(&self, id: usize) -> bool;
+    /// Iterates over the items in the order of their positions.
+    fn iter<'a>(&'a self) -> Box<dyn Iterator<Item = Entry<'a>> + 'a>;
 
-    // TODO: iter()
+    /// Items of the given kind, eg. only equipment.
+    fn of_kind<'a>(&'a self, kind: ItemKind) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        Box::new(self.iter().filter(move |entry| entry.item.kind() == kind))
+    }
+    /// Equipment that occupies the slot when worn.
+    fn for_slot<'a>(&'a self, slot: Slot) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        Box::new(self.iter().filter(move |entry| match *entry.item {
+            Item::Equipment(ref equipment) => equipment.slots().contains(&slot),
+            _ => false,
+        }))
+    }
+    /// Items whose name contains the text, ignoring case.
+    fn named<'a>(&'a self, text: &str) -> Box<dyn Iterator<Item = Entry<'a>> + 'a> {
+        let text = text.to_lowercase();
+        Box::new(
+            self.iter()
+                .filter(move |entry| entry.item.name().to_lowercase().contains(&text)),
+        )
+    }
+}
+
+/// Width and height of an item in a grid inventory.
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Debug)]
+pub struct Footprint {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Footprint {
+    pub fn new(width: usize, height: usize) -> Footprint {
+        Footprint { width, height }
+    }
+    /// The footprint turned by 90 degrees.
+    pub fn rotated(&self) -> Footprint {
+        Footprint::new(self.height, self.width)
+    }
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
 }
 
-#[derive(Clone, Copy, Serialize, Deserialize, Debug)]
+/// An item in a container.
+#[derive(Clone, Copy, Debug)]
+pub struct Entry<'a> {
+    /// The first position reserved by the item.
+    pub position: usize,
+    pub size: usize,
+    pub item: &'a Item,
+}
+
+/// A minimum value of an attribute, eg. Strength 5 for a heavy axe.
+#[derive(Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Debug)]
+pub struct Requirement {
+    pub attribute: Attribute,
+    pub minimum: i32,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, PartialEq, Eq)]
 pub enum ItemEffect {
+    /// Flat bonus to an attribute while the item is worn.
     AttributeModifier(Attribute, i32),
+    /// Flat bonus to any registered stat, eg. "MaxLife", while the item is worn.
+    StatModifier(String, i32),
+    /// Any modifier that applies while the item is worn.
+    Modifier(Modifier),
+    /// Modifier that lasts for a number of combat rounds after the consumable is used.
+    Buff { modifier: Modifier, rounds: u32 },
+}
+
+impl ItemEffect {
+    /// How the effect changes the stat while the item is worn, if at all.
+    pub fn modifier_for(&self, stat: &str) -> Option<(ModifierValue, Option<Condition>)> {
+        match *self {
+            ItemEffect::AttributeModifier(attribute, amount) if attribute.name() == stat => {
+                Some((ModifierValue::Flat(amount), None))
+            }
+            ItemEffect::StatModifier(ref name, amount) if name == stat => {
+                Some((ModifierValue::Flat(amount), None))
+            }
+            ItemEffect::Modifier(ref modifier) if modifier.stat == stat => {
+                Some((modifier.value, modifier.condition))
+            }
+            _ => None,
+        }
+    }
 }
 
 #[derive(PartialEq, Eq, Hash, Serialize, Deserialize, Debug, Clone, Copy, PartialOrd, Ord)]
@@ -190,6 +587,12 @@ impl TryFrom<Item> for Consumable {
     }
 }
 
+impl From<Container> for Item {
+    fn from(original: Container) -> Self {
+        Item::Container(original)
+    }
+}
+
 impl AsRef<Slot> for Slot {
     fn as_ref(&self) -> &Self {
         self
@@ -0,0 +1,68 @@
+use super::*;
+use serde_yaml;
+
+/// Items that grant extra effects when several of them are worn together.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquipmentSet {
+    pub name: String,
+    /// Number of different pieces in the complete set.
+    pub pieces: usize,
+    #[serde(default)]
+    pub bonuses: Vec<SetBonus>,
+}
+
+/// Effects granted while at least `pieces` different pieces of a set are worn.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SetBonus {
+    pub pieces: usize,
+    pub effects: Vec<ItemEffect>,
+}
+
+impl EquipmentSet {
+    pub fn new(name: &str, pieces: usize) -> EquipmentSet {
+        EquipmentSet {
+            name: name.to_owned(),
+            pieces,
+            bonuses: vec![],
+        }
+    }
+    /// Adds a bonus tier for wearing the given number of pieces.
+    pub fn bonus(mut self, pieces: usize, effects: Vec<ItemEffect>) -> EquipmentSet {
+        self.bonuses.push(SetBonus { pieces, effects });
+        self
+    }
+    /// Effects of every tier reached with the number of worn pieces.
+    pub fn effects_for(&self, worn: usize) -> Vec<ItemEffect> {
+        self.bonuses
+            .iter()
+            .filter(|bonus| bonus.pieces <= worn)
+            .flat_map(|bonus| bonus.effects.iter().cloned())
+            .collect()
+    }
+    /// Eg. "Iahu's Regalia (2/4)".
+    pub fn progress(&self, worn: usize) -> String {
+        format!("{} ({}/{})", self.name, worn, self.pieces)
+    }
+}
+
+/// All set definitions of the game, usually loaded from data files.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EquipmentSetLibrary {
+    sets: Vec<EquipmentSet>,
+}
+
+impl EquipmentSetLibrary {
+    pub fn new(sets: Vec<EquipmentSet>) -> EquipmentSetLibrary {
+        EquipmentSetLibrary { sets }
+    }
+    /// Loads a YAML list of sets.
+    pub fn from_yaml(yaml: &str) -> Result<EquipmentSetLibrary, serde_yaml::Error> {
+        Ok(EquipmentSetLibrary::new(serde_yaml::from_str(yaml)?))
+    }
+    pub fn get(&self, name: &str) -> Option<&EquipmentSet> {
+        self.sets.iter().find(|set| set.name == name)
+    }
+    pub fn sets(&self) -> &[EquipmentSet] {
+        self.sets.as_slice()
+    }
+}
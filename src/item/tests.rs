@@ -13,8 +13,8 @@ fn can_equip_items() {
 
     // Act
     {
-        character.equip(sword.clone().into());
-        character.equip(shield.clone().into());
+        character.equip(sword.clone()).unwrap();
+        character.equip(shield.clone()).unwrap();
     }
 
     // Assert
@@ -58,10 +58,10 @@ fn sword_beats_unarmed() {
     let mut attributes = CharacterAttributes::default();
     attributes.set(Attribute::Strength, 0);
     attributes.set(Attribute::Constitution, 5);
-    let mut combatant_a = CharacterBuilder::new(2, 8, attributes.clone()).build();
-    let mut combatant_b = CharacterBuilder::new(2, 8, attributes.clone()).build();
+    let mut combatant_a = CharacterBuilder::new(8, attributes.clone()).build();
+    let mut combatant_b = CharacterBuilder::new(8, attributes.clone()).build();
 
-    combatant_a.equip(sword.into());
+    combatant_a.equip(sword).unwrap();
 
     // Act
     {
@@ -140,3 +140,310 @@ fn complex_inventory() {
     assert!(inventory.take(6).is_none());
     assert_eq!(pos_3.unwrap(), 7);
 }
+
+#[test]
+fn two_handed_weapon_displaces_both_hands() {
+    let sword = equipment("Sword", 1, Hand, vec![]).build();
+    let shield = equipment("Shield", 1, Hand, vec![]).build();
+    let greatsword = equipment("Greatsword", 1, Hand, vec![]).two_handed().build();
+    let mut character = Character::default();
+    character.equip(sword.clone()).unwrap();
+    character.equip(shield.clone()).unwrap();
+
+    let displaced = character.equip(greatsword.clone()).unwrap();
+
+    let names: Vec<String> = displaced.iter().map(|item| item.name()).collect();
+    assert_eq!(names, vec![sword.name(), shield.name()]);
+    let items_in_hand = character.equipment().by_slot(&Hand);
+    assert_eq!(items_in_hand.len(), 1);
+    assert_eq!(items_in_hand[0].name(), greatsword.name());
+    assert_eq!(character.equipment().nth_in_slot(&Hand, 1).unwrap().name(), greatsword.name());
+    let held: Vec<String> = character
+        .equipment()
+        .inner()
+        .iter()
+        .filter(|&&(slot, _)| slot == Hand)
+        .filter_map(|&(_, item)| item.map(|item| item.name()))
+        .collect();
+    assert_eq!(held, vec![greatsword.name(), greatsword.name()]);
+
+    // Equipping a one-handed weapon frees the other hand as well
+    let displaced = character.equip(sword.clone()).unwrap();
+    assert_eq!(displaced.len(), 1);
+    assert_eq!(displaced[0].name(), greatsword.name());
+    let free_hands = (0..character.equipment().inner().len())
+        .filter(|&idx| character.equipment().slots()[idx] == &Hand)
+        .filter(|&idx| character.equipment().is_free(idx))
+        .count();
+    assert_eq!(free_hands, 1);
+}
+
+#[test]
+fn unequipping_any_occupied_slot_removes_multi_slot_item() {
+    let robe = equipment("Robe", 1, Torso, vec![]).also_occupies(Feet).build();
+    let mut character = Character::default();
+    assert!(character.equip(robe.clone()).unwrap().is_empty());
+
+    let feet = character.equipment().slots().iter().position(|&&slot| slot == Feet).unwrap();
+    let torso = character.equipment().slots().iter().position(|&&slot| slot == Torso).unwrap();
+    assert!(!character.equipment().is_free(feet));
+
+    let unequipped = character.unequip(feet).unwrap();
+    assert_eq!(unequipped.name(), robe.name());
+    assert!(character.equipment().is_free(feet));
+    assert!(character.equipment().is_free(torso));
+    assert!(character.unequip(feet).is_none());
+}
+
+#[test]
+fn equipping_without_suitable_slots_fails() {
+    let ring = equipment("Ring", 1, Hand, vec![]).also_occupies(Head).also_occupies(Head).build();
+    let mut character = Character::default();
+    match character.equip(ring.clone()) {
+        Err(EquipError::NoSuitableSlots(item)) => assert_eq!(item.name(), ring.name()),
+        _ => panic!("equipped an item needing two heads"),
+    }
+}
+
+#[test]
+fn iterate_and_filter_inventory() {
+    let mut inventory = Inventory::new(8);
+    inventory.put_at(equipment("Long Sword", 3, Hand, vec![]).build().into(), 1);
+    inventory.put_at(consumable("Potion of Healing", 1, vec![]).build().into(), 4);
+    inventory.put_at(equipment("Helmet", 2, Head, vec![]).build().into(), 6);
+
+    let entries: Vec<(usize, usize, String)> = inventory
+        .iter()
+        .map(|entry| (entry.position, entry.size, entry.item.name()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (1, 3, "Long Sword".to_owned()),
+            (4, 1, "Potion of Healing".to_owned()),
+            (6, 2, "Helmet".to_owned()),
+        ]
+    );
+
+    let positions = |entries: Box<dyn Iterator<Item = Entry>>| -> Vec<usize> {
+        entries.map(|entry| entry.position).collect()
+    };
+    assert_eq!(positions(inventory.of_kind(ItemKind::Equipment)), vec![1, 6]);
+    assert_eq!(positions(inventory.of_kind(ItemKind::Consumable)), vec![4]);
+    assert_eq!(positions(inventory.for_slot(Head)), vec![6]);
+    assert_eq!(positions(inventory.named("healing")), vec![4]);
+}
+
+#[test]
+fn sorting_defragments_inventory() {
+    let mut inventory = Inventory::new(6);
+    inventory.put_at(equipment("Dagger", 1, Hand, vec![]).build().into(), 1);
+    inventory.put_at(equipment("Axe", 1, Hand, vec![]).build().into(), 3);
+    inventory.put_at(equipment("Long Sword", 2, Hand, vec![]).build().into(), 4);
+    let spear = equipment("Spear", 2, Hand, vec![]).build();
+    assert!(inventory.put(spear.clone().into()).is_none());
+
+    inventory.compact();
+    let names: Vec<String> = inventory.iter().map(|entry| entry.item.name()).collect();
+    assert_eq!(names, vec!["Dagger", "Axe", "Long Sword"]);
+    assert_eq!(inventory.bounds(3), (2, 2));
+
+    inventory.sort();
+    let entries: Vec<(usize, String)> = inventory
+        .iter()
+        .map(|entry| (entry.position, entry.item.name()))
+        .collect();
+    assert_eq!(
+        entries,
+        vec![
+            (0, "Long Sword".to_owned()),
+            (2, "Axe".to_owned()),
+            (3, "Dagger".to_owned()),
+        ]
+    );
+    assert_eq!(inventory.put(spear.into()), Some(4));
+}
+
+#[test]
+fn grid_inventory_places_footprints() {
+    // 4x3 grid
+    let mut inventory = GridInventory::new(4, 3);
+    let bow = equipment("Bow", 0, Hand, vec![]).footprint(1, 3).build();
+    let shield = equipment("Shield", 0, Hand, vec![]).footprint(2, 2).build();
+    let spear = equipment("Spear", 0, Hand, vec![]).footprint(3, 1).build();
+
+    assert_eq!(inventory.put(bow.into()), Some(0));
+    assert_eq!(inventory.put(shield.into()), Some(1));
+    // Fits best when rotated into the right column
+    assert_eq!(inventory.put(spear.clone().into()), Some(3));
+    assert_eq!(inventory.placement(11), Some(Placement { position: 3, rotated: true }));
+    assert_eq!(inventory.get(6).unwrap().name(), "Shield");
+    assert_eq!(inventory.capacity(), 12);
+
+    let entries: Vec<(usize, usize)> = inventory.iter().map(|e| (e.position, e.size)).collect();
+    assert_eq!(entries, vec![(0, 3), (1, 4), (3, 3)]);
+
+    // Unrotated the spear only fits in the bottom row
+    assert_eq!(inventory.take(7).unwrap().name(), "Spear");
+    assert!(!inventory.put_at(spear.clone().into(), 3, false));
+    assert!(inventory.put_at(spear.into(), 9, false));
+    assert_eq!(inventory.get(11).unwrap().name(), "Spear");
+    assert!(!inventory.is_reserved(7));
+}
+
+#[test]
+fn grid_inventory_moves_and_swaps_items() {
+    let mut inventory = GridInventory::new(3, 2);
+    let dagger = equipment("Dagger", 0, Hand, vec![]).footprint(1, 1).build();
+    let sword = equipment("Sword", 0, Hand, vec![]).footprint(2, 1).build();
+    inventory.put_at(dagger.into(), 0, false);
+    inventory.put_at(sword.into(), 4, false);
+
+    // The sword cannot be swapped to the dagger's place in the corner...
+    assert!(inventory.move_item(0, 2, false));
+    assert!(!inventory.swap(2, 4));
+    assert_eq!(inventory.get(2).unwrap().name(), "Dagger");
+    assert_eq!(inventory.get(5).unwrap().name(), "Sword");
+
+    // ...but it can be after the dagger has moved
+    assert!(inventory.move_item(2, 0, false));
+    assert!(inventory.swap(0, 5));
+    assert_eq!(inventory.get(1).unwrap().name(), "Sword");
+    assert_eq!(inventory.get(4).unwrap().name(), "Dagger");
+
+    // The dagger is in the way of rotating the sword
+    assert!(inventory.move_item(4, 3, false));
+    assert!(!inventory.rotate(0));
+    assert!(inventory.move_item(3, 5, false));
+    assert!(inventory.rotate(0));
+    assert_eq!(inventory.get(3).unwrap().name(), "Sword");
+    assert!(!inventory.is_reserved(1));
+}
+
+#[test]
+fn consumables_stack_in_inventory() {
+    let potions = |count| {
+        consumable("healing potion", 1, vec![])
+            .stack_limit(5)
+            .count(count)
+            .build()
+    };
+    let mut inventory = Inventory::new(3);
+    inventory.put_at(equipment("Dagger", 1, Hand, vec![]).build().into(), 0);
+
+    assert_eq!(inventory.put(potions(3).into()), Some(1));
+    // Tops up the first stack and starts a new one with the rest
+    assert_eq!(inventory.put(potions(4).into()), Some(2));
+    assert_eq!(inventory.get(1).unwrap().quantity(), 5);
+    assert_eq!(inventory.get(2).unwrap().quantity(), 2);
+    assert_eq!(inventory.get(2).unwrap().quantified_name(), "two healing potions");
+    assert_eq!(inventory.free_space(), 0);
+    assert_eq!(inventory.room_for(&potions(1)), 3);
+
+    // Nothing changes if everything doesn't fit
+    assert_eq!(inventory.put(potions(4).into()), None);
+    assert_eq!(inventory.get(2).unwrap().quantity(), 2);
+    assert_eq!(inventory.put(potions(3).into()), Some(2));
+    assert_eq!(inventory.get(2).unwrap().quantity(), 5);
+
+    // Different uses do not stack
+    let used = consumable("healing potion", 1, vec![])
+        .stack_limit(5)
+        .remaining_uses(0, 1)
+        .build();
+    assert!(!used.stacks_with(&potions(1)));
+}
+
+#[test]
+fn stack_counts_are_clamped() {
+    let potion = consumable("healing potion", 1, vec![]).stack_limit(5).build();
+    let overfull = consumable("healing potion", 1, vec![]).stack_limit(5).count(9).build();
+    assert_eq!(overfull.count(), 5);
+
+    let yaml = ::serde_yaml::to_string(&potion).unwrap();
+    let empty: Consumable = ::serde_yaml::from_str(&yaml.replace("count: 1", "count: 0")).unwrap();
+    let overfull: Consumable = ::serde_yaml::from_str(&yaml.replace("count: 1", "count: 9")).unwrap();
+    assert_eq!(empty.count(), 1);
+    assert_eq!(overfull.count(), 5);
+
+    // Stacks like any other potion
+    let mut inventory = Inventory::new(2);
+    inventory.put(potion.clone().into());
+    assert_eq!(inventory.put(empty.into()), Some(0));
+    assert_eq!(inventory.get(0).unwrap().quantity(), 2);
+}
+
+#[test]
+fn split_and_merge_stacks() {
+    let arrows = consumable("arrow", 1, vec![]).stack_limit(20).count(12).build();
+    let mut inventory = Inventory::new(3);
+    inventory.put(arrows.into());
+
+    assert_eq!(inventory.split(0, 5), Some(1));
+    assert_eq!(inventory.get(0).unwrap().quantified_name(), "seven arrows");
+    assert_eq!(inventory.get(1).unwrap().quantified_name(), "five arrows");
+    assert_eq!(inventory.split(0, 7), None);
+
+    assert!(inventory.merge(0, 1));
+    assert_eq!(inventory.get(1).unwrap().quantity(), 12);
+    assert!(!inventory.is_reserved(0));
+    assert_eq!(inventory.iter().count(), 1);
+    assert!(!inventory.merge(1, 1));
+}
+
+#[test]
+fn containers_hold_items() {
+    let arrows = consumable("arrow", 1, vec![])
+        .tagged("arrow")
+        .weight(1)
+        .stack_limit(20)
+        .count(10)
+        .build();
+    let mut quiver = container("quiver", 2, 2).accepts("arrow").weight(3).build();
+
+    assert_eq!(quiver.put(equipment("Dagger", 1, Hand, vec![]).build().into()), None);
+    assert_eq!(quiver.put(arrows.into()), Some(0));
+    assert_eq!(Item::from(quiver.clone()).weight(), 13);
+
+    // Takes its own size in the parent regardless of the contents
+    let mut inventory = Inventory::new(4);
+    assert_eq!(inventory.put(quiver.into()), Some(0));
+    assert_eq!(inventory.free_space(), 2);
+    assert_eq!(inventory.get_path(&[1, 0]).unwrap().quantity(), 10);
+}
+
+#[test]
+fn search_and_move_through_nested_containers() {
+    let potion = consumable("healing potion", 1, vec![]).build();
+    let pouch = container("pouch", 1, 2).build();
+    let mut backpack = container("backpack", 2, 4).build();
+    backpack.put(pouch.into());
+    backpack.put(potion.into());
+    let mut inventory = Inventory::new(4);
+    inventory.put(equipment("Dagger", 1, Hand, vec![]).build().into());
+    inventory.put(backpack.into());
+
+    let found = inventory.find(&|item: &Item| item.name() == "healing potion");
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].0, vec![1, 1]);
+    let containers = inventory.find(&|item: &Item| item.kind() == ItemKind::Container);
+    let paths: Vec<Vec<usize>> = containers.into_iter().map(|(path, _)| path).collect();
+    assert_eq!(paths, vec![vec![1], vec![1, 0]]);
+
+    // Move the potion into the pouch, addressing the backpack by any of its positions
+    assert_eq!(inventory.move_item(&[2, 1], &[1, 0]), Ok(0));
+    assert_eq!(inventory.get_path(&[1, 0, 0]).unwrap().name(), "healing potion");
+
+    // A bag can't go inside itself or a bag within it
+    let before = inventory.clone();
+    assert_eq!(inventory.move_item(&[1], &[1]), Err(ContainerError::IntoItself));
+    assert_eq!(inventory.move_item(&[2], &[1, 0]), Err(ContainerError::IntoItself));
+    assert_eq!(inventory.move_item(&[1], &[0]), Err(ContainerError::NotAContainer));
+    assert_eq!(inventory.move_item(&[3], &[]), Err(ContainerError::NotFound));
+    assert_eq!(inventory, before);
+
+    // Bags may go in other bags
+    assert_eq!(inventory.move_item(&[1, 0], &[]), Ok(3));
+    assert_eq!(inventory.move_item(&[0], &[3]), Ok(1));
+    assert_eq!(inventory.get_path(&[3, 1]).unwrap().name(), "Dagger");
+}
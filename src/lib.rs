@@ -8,7 +8,8 @@ extern crate range;
 extern crate rand;
 #[macro_use]
 extern crate lazy_static;
-extern crate rustache;
+#[cfg(test)]
+extern crate serde_json;
 
 // Keep the #[macro use] utils first
 #[macro_use]
@@ -20,6 +21,10 @@ pub mod display;
 pub mod monster;
 pub mod theme;
 pub mod dungeon;
+pub mod entity;
+pub mod skill;
+pub mod party;
+pub mod stat;
 
 #[cfg(test)]
 mod tests;
@@ -32,3 +37,7 @@ pub use theme::*;
 pub use display::*;
 pub use monster::*;
 pub use dungeon::*;
+pub use entity::*;
+pub use skill::*;
+pub use party::*;
+pub use stat::*;
@@ -7,12 +7,26 @@ use item::Equipment;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Monster {
+    #[serde(default)]
+    id: EntityId,
     life: i32,
     damage: i32,
     name: String,
     /// Designer defined difficulty
     difficulty: Option<usize>,
     keywords: Vec<Keyword>,
+    /// Named monsters, eg. "Gzolneb the Crusher", are proper nouns.
+    #[serde(default)]
+    noun_kind: NounKind,
+    /// Determines the amount of actions per round.
+    #[serde(default = "default_swiftness")]
+    swiftness: i32,
+    #[serde(default)]
+    action_formula: ActionFormula,
+}
+
+fn default_swiftness() -> i32 {
+    1
 }
 
 /// Template monsters represent themed variants of monsters. Template monsters
@@ -48,11 +62,15 @@ impl MonsterBuilder {
     pub fn new(name: &str, damage: i32, life: i32) -> Self {
         MonsterBuilder {
             monster: Monster {
+                id: EntityId::new(),
                 name: name.to_string(),
                 damage: damage,
                 life: life,
                 difficulty: None,
                 keywords: vec![],
+                noun_kind: NounKind::Common,
+                swiftness: default_swiftness(),
+                action_formula: ActionFormula::default(),
             },
         }
     }
@@ -68,6 +86,20 @@ impl MonsterBuilder {
         self.monster.keywords.extend(keywords);
         self
     }
+    pub fn swiftness(mut self, swiftness: i32) -> Self {
+        self.monster.swiftness = swiftness;
+        self
+    }
+    /// Sets how Swiftness is converted into actions per round.
+    pub fn action_formula(mut self, formula: ActionFormula) -> Self {
+        self.monster.action_formula = formula;
+        self
+    }
+    /// Marks the name as a proper noun, ie. the monster is a named individual.
+    pub fn proper_noun(mut self) -> Self {
+        self.monster.noun_kind = NounKind::Proper;
+        self
+    }
     pub fn keyword(mut self, keyword: &str) -> Self {
         self.monster.keywords.push(Keyword {
             id: keyword.to_string(),
@@ -77,7 +109,7 @@ impl MonsterBuilder {
     // TODO: from template
     // Spawn a copy of the generated monster
     pub fn spawn(&self) -> Monster {
-        self.monster.clone()
+        self.monster.spawn()
     }
 }
 
@@ -85,6 +117,21 @@ lazy_static! {
     static ref DEFAULT_MONSTER_WEAPON: Equipment = equipment("fist", 1, Slot::Hand, vec![]).build();
 }
 
+impl Monster {
+    /// Returns a copy of the monster as a new entity with its own identifier.
+    pub fn spawn(&self) -> Monster {
+        let mut monster = self.clone();
+        monster.id = EntityId::new();
+        monster
+    }
+}
+
+impl Entity for Monster {
+    fn id(&self) -> EntityId {
+        self.id
+    }
+}
+
 impl Combatant for Monster {
     fn best_weapon(&self) -> &Equipment {
         &DEFAULT_MONSTER_WEAPON
@@ -93,7 +140,7 @@ impl Combatant for Monster {
         self.damage
     }
     fn action_buffer(&self) -> ActionBuffer {
-        ActionBuffer::default()
+        ActionBuffer::filled(self.action_formula.actions(self.swiftness), &Action::Attack)
     }
     fn set_life(&mut self, amount: i32) -> i32 {
         self.life = max(amount, 0);
@@ -111,6 +158,9 @@ impl Display for Monster {
     fn name(&self) -> String {
         self.name.clone()
     }
+    fn noun_kind(&self) -> NounKind {
+        self.noun_kind
+    }
 }
 
 impl<'a> Evaluate for Monster {
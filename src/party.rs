@@ -0,0 +1,212 @@
+use super::*;
+use item::Equipment;
+use std::cmp::min;
+
+/// Who carries the loot of a party.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InventoryPolicy {
+    /// Every member uses their own inventory.
+    PerMember,
+    /// All members use one inventory owned by the party.
+    Shared,
+}
+
+/// Several characters adventuring together.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Party {
+    /// Members in marching order, the front member is attacked first.
+    members: Vec<Character>,
+    leader: EntityId,
+    policy: InventoryPolicy,
+    /// Used only with the shared inventory policy.
+    shared_inventory: Inventory,
+}
+
+impl Party {
+    /// Creates a party of one where every member carries their own items.
+    pub fn new(leader: Character) -> Party {
+        Party {
+            leader: leader.id(),
+            members: vec![leader],
+            policy: InventoryPolicy::PerMember,
+            shared_inventory: Inventory::new(0),
+        }
+    }
+    /// Makes the members use a shared inventory of the given capacity. Items
+    /// already carried by the members stay in their own inventories.
+    pub fn share_inventory(mut self, capacity: usize) -> Party {
+        self.policy = InventoryPolicy::Shared;
+        self.shared_inventory = Inventory::new(capacity);
+        self
+    }
+    pub fn inventory_policy(&self) -> InventoryPolicy {
+        self.policy
+    }
+    /// Members in marching order.
+    pub fn members(&self) -> &[Character] {
+        self.members.as_slice()
+    }
+    pub fn member(&self, id: EntityId) -> Option<&Character> {
+        self.members.iter().find(|member| member.id() == id)
+    }
+    pub fn member_mut(&mut self, id: EntityId) -> Option<&mut Character> {
+        self.members.iter_mut().find(|member| member.id() == id)
+    }
+    /// Adds the character at the rear of the marching order.
+    pub fn join(&mut self, character: Character) {
+        self.members.push(character);
+    }
+    /// Removes the member from the party. The front member becomes the leader
+    /// if the leader leaves. The last member cannot leave.
+    pub fn leave(&mut self, id: EntityId) -> Option<Character> {
+        if self.members.len() == 1 {
+            return None;
+        }
+        let position = self.position(id)?;
+        let member = self.members.remove(position);
+        if self.leader == id {
+            self.leader = self.members[0].id();
+        }
+        Some(member)
+    }
+    pub fn leader(&self) -> &Character {
+        self.member(self.leader)
+            .expect("the leader is always a member of the party")
+    }
+    /// Returns false if the character is not a member.
+    pub fn set_leader(&mut self, id: EntityId) -> bool {
+        if self.member(id).is_none() {
+            return false;
+        }
+        self.leader = id;
+        true
+    }
+    /// Position of the member in the marching order, 0 is the front.
+    pub fn position(&self, id: EntityId) -> Option<usize> {
+        self.members.iter().position(|member| member.id() == id)
+    }
+    /// Moves the member to the position in the marching order, or to the rear
+    /// if the position is past the end. Returns false if the character is not a member.
+    pub fn move_to(&mut self, id: EntityId, position: usize) -> bool {
+        match self.position(id) {
+            Some(current) => {
+                let member = self.members.remove(current);
+                let position = min(position, self.members.len());
+                self.members.insert(position, member);
+                true
+            }
+            None => false,
+        }
+    }
+    /// Members that are still able to fight, in marching order.
+    pub fn living(&self) -> Vec<&Character> {
+        self.members.iter().filter(|member| member.can_combat()).collect()
+    }
+    pub fn all_dead(&self) -> bool {
+        self.living().is_empty()
+    }
+    /// The first living member in the marching order.
+    pub fn front(&self) -> Option<&Character> {
+        self.members.iter().find(|member| member.can_combat())
+    }
+    /// The inventory the member puts items into, depending on the inventory policy.
+    pub fn inventory_of(&self, id: EntityId) -> Option<&Inventory> {
+        let member = self.member(id)?;
+        match self.policy {
+            InventoryPolicy::PerMember => Some(&member.inventory),
+            InventoryPolicy::Shared => Some(&self.shared_inventory),
+        }
+    }
+    pub fn inventory_of_mut(&mut self, id: EntityId) -> Option<&mut Inventory> {
+        match self.policy {
+            InventoryPolicy::PerMember => self.member_mut(id).map(|member| &mut member.inventory),
+            InventoryPolicy::Shared => {
+                self.member(id)?;
+                Some(&mut self.shared_inventory)
+            }
+        }
+    }
+}
+
+/// Every living member takes part in combat. Damage taken by the party hits
+/// the front member first, anything left over hits the next member in line.
+impl Combatant for Party {
+    fn life(&self) -> i32 {
+        self.living().iter().map(|member| member.life()).sum()
+    }
+    fn set_life(&mut self, amount: i32) -> i32 {
+        let mut change = amount - self.life();
+        for member in self.members.iter_mut().filter(|member| member.can_combat()) {
+            if change == 0 {
+                break;
+            }
+            let life = member.life();
+            if change > 0 {
+                // Healing goes to the front member only
+                member.set_life(life + change);
+                change = 0;
+            } else {
+                let damage = min(-change, life);
+                member.set_life(life - damage);
+                change += damage;
+            }
+        }
+        self.life()
+    }
+    fn can_combat(&self) -> bool {
+        !self.all_dead()
+    }
+    fn action_buffer(&self) -> ActionBuffer {
+        let buffers: Vec<ActionBuffer> = self
+            .living()
+            .iter()
+            .map(|member| member.action_buffer())
+            .collect();
+        let mut buffer = ActionBuffer::new(buffers.iter().map(|b| b.max_actions()).sum());
+        for action in buffers.iter().flat_map(|buffer| buffer.actions()) {
+            buffer.push(action);
+        }
+        buffer
+    }
+    /// Combined damage of the living members.
+    fn damage(&self) -> i32 {
+        self.living().iter().map(|member| member.damage()).sum()
+    }
+    fn best_weapon(&self) -> &Equipment {
+        self.front().unwrap_or_else(|| self.leader()).best_weapon()
+    }
+    /// Debuffs from opponents hit the front member.
+    fn apply_timed_modifier(&mut self, modifier: Modifier, rounds: u32, source: &str) {
+        if let Some(front) = self.members.iter_mut().find(|member| member.can_combat()) {
+            front.add_timed_modifier(modifier, rounds, source);
+        }
+    }
+    fn end_round(&mut self) {
+        for member in &mut self.members {
+            member.end_round();
+        }
+    }
+    /// Every living member performs their own actions. Members heal themselves
+    /// right away instead of the front member.
+    fn perform_actions(&mut self) -> Performance {
+        let mut performance = Performance::default();
+        for member in self.members.iter_mut().filter(|member| member.can_combat()) {
+            let own = member.perform_actions();
+            member.heal(own.healing);
+            performance.hits.extend(own.hits);
+            performance.debuffs.extend(own.debuffs);
+            performance.used.extend(own.used);
+        }
+        performance
+    }
+}
+
+/// Parties are named after their leader, eg. "Bob's party".
+impl Display for Party {
+    fn name(&self) -> String {
+        format!("{}'s party", self.leader().name())
+    }
+    fn noun_kind(&self) -> NounKind {
+        NounKind::Proper
+    }
+}
@@ -0,0 +1,92 @@
+use character::Modifier;
+use item::Requirement;
+use serde_yaml;
+
+/// What happens when a skill is used in combat.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SkillEffect {
+    /// Hits the opponent for a percentage of normal damage plus a bonus.
+    Strike { damage_percent: i32, bonus_damage: i32 },
+    /// Restores life of the user.
+    Heal(i32),
+    /// Modifier on the user for a number of rounds, counting the current one.
+    Buff { modifier: Modifier, rounds: u32 },
+    /// Modifier on the opponent for a number of rounds, starting with the next
+    /// one since the opponent has already acted in the current one.
+    Debuff { modifier: Modifier, rounds: u32 },
+}
+
+/// An ability that can be used in combat instead of a normal attack.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Skill {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Stamina spent on every use, before encumbrance.
+    #[serde(default)]
+    pub stamina_cost: i32,
+    /// Number of rounds after use before the skill can be used again.
+    #[serde(default)]
+    pub cooldown: u32,
+    /// Minimum base attributes required to learn the skill.
+    #[serde(default)]
+    pub prerequisites: Vec<Requirement>,
+    /// Level at which the skill can be learned without a teacher. Skills
+    /// without a level can only be learned from items.
+    #[serde(default)]
+    pub level: Option<u32>,
+    pub effects: Vec<SkillEffect>,
+}
+
+impl Skill {
+    pub fn new(name: &str, stamina_cost: i32, cooldown: u32, effects: Vec<SkillEffect>) -> Skill {
+        Skill {
+            name: name.to_owned(),
+            description: String::new(),
+            stamina_cost,
+            cooldown,
+            prerequisites: vec![],
+            level: None,
+            effects,
+        }
+    }
+}
+
+/// All skill definitions of the game, usually loaded from data files.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct SkillLibrary {
+    skills: Vec<Skill>,
+}
+
+impl SkillLibrary {
+    pub fn new(skills: Vec<Skill>) -> SkillLibrary {
+        SkillLibrary { skills }
+    }
+    /// Loads a YAML list of skills.
+    pub fn from_yaml(yaml: &str) -> Result<SkillLibrary, serde_yaml::Error> {
+        Ok(SkillLibrary::new(serde_yaml::from_str(yaml)?))
+    }
+    pub fn get(&self, name: &str) -> Option<&Skill> {
+        self.skills.iter().find(|skill| skill.name == name)
+    }
+    pub fn skills(&self) -> &[Skill] {
+        self.skills.as_slice()
+    }
+}
+
+/// Reasons why a skill could not be learned or used.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkillError {
+    /// The library has no skill with the name.
+    Unknown(String),
+    AlreadyKnown,
+    NotKnown,
+    UnmetPrerequisites(Vec<::character::UnmetRequirement>),
+    /// Rounds left before the skill can be used again.
+    OnCooldown(u32),
+    /// The skill is already queued for the next round.
+    AlreadyQueued,
+    NotEnoughStamina { required: i32, available: i32 },
+    /// All actions of the round have already been reserved.
+    NoFreeAction,
+}
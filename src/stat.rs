@@ -0,0 +1,427 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use serde_yaml;
+use character::{CARRY_LIMIT_PER_STRENGTH, LIFE_PER_CONSTITUTION, STAMINA_PER_ENDURANCE};
+use combat::ActionFormula;
+use std::cmp::{max, min};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Arithmetic on stats, eg. `max(Constitution * 2, 1)`. Supports integers,
+/// stat names, `+ - * /`, parentheses and the functions `min` and `max`.
+/// Division rounds towards zero and division by zero gives zero.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Formula {
+    source: String,
+    expression: Expression,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Expression {
+    Number(i32),
+    Stat(String),
+    Negate(Box<Expression>),
+    Binary(Operator, Box<Expression>, Box<Expression>),
+    Min(Vec<Expression>),
+    Max(Vec<Expression>),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Operator {
+    Add,
+    Subtract,
+    Multiply,
+    Divide,
+}
+
+impl Formula {
+    pub fn parse(source: &str) -> Result<Formula, String> {
+        let mut parser = Parser {
+            chars: source.chars().collect(),
+            position: 0,
+        };
+        let expression = parser.expression()?;
+        parser.skip_whitespace();
+        if parser.position < parser.chars.len() {
+            return Err(format!("unexpected '{}' in \"{}\"", parser.chars[parser.position], source));
+        }
+        Ok(Formula {
+            source: source.to_owned(),
+            expression,
+        })
+    }
+    /// Computes the value, looking up the values of the stats it refers to.
+    pub fn evaluate<F: Fn(&str) -> i32>(&self, stat: &F) -> i32 {
+        self.expression.evaluate(stat)
+    }
+    /// Names of all stats the formula refers to.
+    pub fn references(&self) -> Vec<&str> {
+        let mut references = vec![];
+        self.expression.collect_references(&mut references);
+        references
+    }
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+}
+
+impl Expression {
+    fn evaluate<F: Fn(&str) -> i32>(&self, stat: &F) -> i32 {
+        match *self {
+            Expression::Number(value) => value,
+            Expression::Stat(ref name) => stat(name),
+            Expression::Negate(ref inner) => -inner.evaluate(stat),
+            Expression::Binary(operator, ref left, ref right) => {
+                let (left, right) = (left.evaluate(stat), right.evaluate(stat));
+                match operator {
+                    Operator::Add => left.saturating_add(right),
+                    Operator::Subtract => left.saturating_sub(right),
+                    Operator::Multiply => left.saturating_mul(right),
+                    Operator::Divide => left.checked_div(right).unwrap_or(0),
+                }
+            }
+            Expression::Min(ref arguments) => arguments
+                .iter()
+                .map(|argument| argument.evaluate(stat))
+                .fold(i32::MAX, min),
+            Expression::Max(ref arguments) => arguments
+                .iter()
+                .map(|argument| argument.evaluate(stat))
+                .fold(i32::MIN, max),
+        }
+    }
+    fn collect_references<'a>(&'a self, references: &mut Vec<&'a str>) {
+        match *self {
+            Expression::Number(_) => {}
+            Expression::Stat(ref name) => references.push(name),
+            Expression::Negate(ref inner) => inner.collect_references(references),
+            Expression::Binary(_, ref left, ref right) => {
+                left.collect_references(references);
+                right.collect_references(references);
+            }
+            Expression::Min(ref arguments) | Expression::Max(ref arguments) => {
+                for argument in arguments {
+                    argument.collect_references(references);
+                }
+            }
+        }
+    }
+}
+
+/// Recursive descent parser for formulas.
+struct Parser {
+    chars: Vec<char>,
+    position: usize,
+}
+
+impl Parser {
+    fn skip_whitespace(&mut self) {
+        while self.position < self.chars.len() && self.chars[self.position].is_whitespace() {
+            self.position += 1;
+        }
+    }
+    fn peek(&mut self) -> Option<char> {
+        self.skip_whitespace();
+        self.chars.get(self.position).cloned()
+    }
+    fn expect(&mut self, expected: char) -> Result<(), String> {
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += 1;
+                Ok(())
+            }
+            Some(c) => Err(format!("expected '{}' but found '{}'", expected, c)),
+            None => Err(format!("expected '{}' but the formula ended", expected)),
+        }
+    }
+    /// expression = term (("+" | "-") term)*
+    fn expression(&mut self) -> Result<Expression, String> {
+        let mut left = self.term()?;
+        loop {
+            let operator = match self.peek() {
+                Some('+') => Operator::Add,
+                Some('-') => Operator::Subtract,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.term()?));
+        }
+    }
+    /// term = factor (("*" | "/") factor)*
+    fn term(&mut self) -> Result<Expression, String> {
+        let mut left = self.factor()?;
+        loop {
+            let operator = match self.peek() {
+                Some('*') => Operator::Multiply,
+                Some('/') => Operator::Divide,
+                _ => return Ok(left),
+            };
+            self.position += 1;
+            left = Expression::Binary(operator, Box::new(left), Box::new(self.factor()?));
+        }
+    }
+    /// factor = number | name | function "(" arguments ")" | "(" expression ")" | "-" factor
+    fn factor(&mut self) -> Result<Expression, String> {
+        match self.peek() {
+            Some('-') => {
+                self.position += 1;
+                Ok(Expression::Negate(Box::new(self.factor()?)))
+            }
+            Some('(') => {
+                self.position += 1;
+                let inner = self.expression()?;
+                self.expect(')')?;
+                Ok(inner)
+            }
+            Some(c) if c.is_ascii_digit() => {
+                let digits = self.take_while(|c| c.is_ascii_digit());
+                digits
+                    .parse()
+                    .map(Expression::Number)
+                    .map_err(|_| format!("number {} is too large", digits))
+            }
+            Some(c) if c.is_alphabetic() || c == '_' => {
+                let name = self.take_while(|c| c.is_alphanumeric() || c == '_');
+                if self.peek() != Some('(') {
+                    return Ok(Expression::Stat(name));
+                }
+                let arguments = self.arguments()?;
+                match name.as_str() {
+                    "min" => Ok(Expression::Min(arguments)),
+                    "max" => Ok(Expression::Max(arguments)),
+                    _ => Err(format!("unknown function {}", name)),
+                }
+            }
+            Some(c) => Err(format!("unexpected '{}'", c)),
+            None => Err("the formula ended unexpectedly".to_owned()),
+        }
+    }
+    fn arguments(&mut self) -> Result<Vec<Expression>, String> {
+        self.expect('(')?;
+        let mut arguments = vec![self.expression()?];
+        while self.peek() == Some(',') {
+            self.position += 1;
+            arguments.push(self.expression()?);
+        }
+        self.expect(')')?;
+        Ok(arguments)
+    }
+    fn take_while<P: Fn(char) -> bool>(&mut self, predicate: P) -> String {
+        let start = self.position;
+        while self.position < self.chars.len() && predicate(self.chars[self.position]) {
+            self.position += 1;
+        }
+        self.chars[start..self.position].iter().collect()
+    }
+}
+
+impl fmt::Display for Formula {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.source)
+    }
+}
+
+/// Formulas are stored as text in data files.
+impl Serialize for Formula {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Formula {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Formula, D::Error> {
+        let source = String::deserialize(deserializer)?;
+        Formula::parse(&source).map_err(D::Error::custom)
+    }
+}
+
+/// A stat is either primary, ie. it has a base value of its own, or derived
+/// from other stats with a formula.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    /// Base value of a primary stat when the character has none of its own.
+    #[serde(default)]
+    pub default: i32,
+    #[serde(default)]
+    pub formula: Option<Formula>,
+}
+
+impl StatDefinition {
+    pub fn primary(name: &str, default: i32) -> StatDefinition {
+        StatDefinition {
+            name: name.to_owned(),
+            description: String::new(),
+            default,
+            formula: None,
+        }
+    }
+    /// Panics if the formula cannot be parsed.
+    pub fn derived(name: &str, formula: &str) -> StatDefinition {
+        StatDefinition {
+            name: name.to_owned(),
+            description: String::new(),
+            default: 0,
+            formula: Some(Formula::parse(formula).expect("invalid formula")),
+        }
+    }
+    pub fn is_derived(&self) -> bool {
+        self.formula.is_some()
+    }
+}
+
+/// Reasons why stat definitions were rejected.
+#[derive(Debug)]
+pub enum StatError {
+    Yaml(serde_yaml::Error),
+    /// Two stats have the same name.
+    Duplicate(String),
+    /// The formula of the stat refers to a stat that is not defined.
+    UnknownReference { stat: String, reference: String },
+    /// The formula of the stat depends on the stat itself.
+    Cycle(String),
+}
+
+impl From<serde_yaml::Error> for StatError {
+    fn from(error: serde_yaml::Error) -> StatError {
+        StatError::Yaml(error)
+    }
+}
+
+/// All stats of the game, usually loaded from data files. The stats are
+/// checked when the registry is created, so evaluating them never loops.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+#[serde(transparent)]
+pub struct StatRegistry {
+    stats: Vec<StatDefinition>,
+}
+
+impl StatRegistry {
+    pub fn new(stats: Vec<StatDefinition>) -> Result<StatRegistry, StatError> {
+        let registry = StatRegistry { stats };
+        registry.validate()?;
+        Ok(registry)
+    }
+    /// Loads a YAML list of stat definitions.
+    pub fn from_yaml(yaml: &str) -> Result<StatRegistry, StatError> {
+        StatRegistry::new(serde_yaml::from_str(yaml)?)
+    }
+    pub fn get(&self, name: &str) -> Option<&StatDefinition> {
+        self.stats.iter().find(|stat| stat.name == name)
+    }
+    pub fn stats(&self) -> &[StatDefinition] {
+        self.stats.as_slice()
+    }
+    /// Adds the stat, or replaces the definition of a stat with the same name.
+    /// Nothing is changed if the stats would no longer be valid.
+    pub fn define(&mut self, stat: StatDefinition) -> Result<(), StatError> {
+        let mut stats = self.stats.clone();
+        match stats.iter().position(|defined| defined.name == stat.name) {
+            Some(position) => stats[position] = stat,
+            None => stats.push(stat),
+        }
+        *self = StatRegistry::new(stats)?;
+        Ok(())
+    }
+    /// Computes the value of the stat. `primary` gives the values of primary
+    /// stats and `bonus` is added to every stat, derived ones included.
+    /// None if the stat is not registered.
+    pub fn evaluate<P, B>(&self, name: &str, primary: &P, bonus: &B) -> Option<i32>
+    where
+        P: Fn(&StatDefinition) -> i32,
+        B: Fn(&str) -> i32,
+    {
+        let stat = self.get(name)?;
+        let value = match stat.formula {
+            None => primary(stat),
+            Some(ref formula) => formula.evaluate(&|reference: &str| {
+                self.evaluate(reference, primary, bonus).unwrap_or(0)
+            }),
+        };
+        Some(value.saturating_add(bonus(name)))
+    }
+    fn validate(&self) -> Result<(), StatError> {
+        let mut dependencies: HashMap<&str, Vec<&str>> = HashMap::new();
+        for stat in &self.stats {
+            let references = match stat.formula {
+                Some(ref formula) => formula.references(),
+                None => vec![],
+            };
+            for &reference in &references {
+                if self.get(reference).is_none() {
+                    return Err(StatError::UnknownReference {
+                        stat: stat.name.clone(),
+                        reference: reference.to_owned(),
+                    });
+                }
+            }
+            if dependencies.insert(&stat.name, references).is_some() {
+                return Err(StatError::Duplicate(stat.name.clone()));
+            }
+        }
+        for stat in &self.stats {
+            if depends_on(&dependencies, &stat.name, &stat.name, &mut vec![]) {
+                return Err(StatError::Cycle(stat.name.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// True if `from` depends on `target`, directly or through other stats.
+fn depends_on<'a>(
+    dependencies: &HashMap<&'a str, Vec<&'a str>>,
+    from: &'a str,
+    target: &str,
+    visited: &mut Vec<&'a str>,
+) -> bool {
+    for &next in &dependencies[from] {
+        if next == target {
+            return true;
+        }
+        if !visited.contains(&next) {
+            visited.push(next);
+            if depends_on(dependencies, next, target, visited) {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// The four attributes and the stats that the library itself uses.
+/// "WeaponDamage" is the damage of the weapon the character hits with.
+impl Default for StatRegistry {
+    fn default() -> StatRegistry {
+        StatRegistry::new(vec![
+            StatDefinition::primary("Strength", 1),
+            StatDefinition::primary("Constitution", 1),
+            StatDefinition::primary("Endurance", 1),
+            StatDefinition::primary("Swiftness", 1),
+            StatDefinition::primary("WeaponDamage", 0),
+            StatDefinition::derived(
+                "MaxLife",
+                &format!("Constitution * {}", LIFE_PER_CONSTITUTION),
+            ),
+            StatDefinition::derived(
+                "MaxStamina",
+                &format!("Endurance * {}", STAMINA_PER_ENDURANCE),
+            ),
+            StatDefinition::derived("Damage", "WeaponDamage + Strength"),
+            StatDefinition::derived(
+                "CarryLimit",
+                &format!("Strength * {}", CARRY_LIMIT_PER_STRENGTH),
+            ),
+            StatDefinition::derived("ActionsPerRound", ActionFormula::default().formula().source()),
+        ]).expect("the default stats are valid")
+    }
+}
+
+impl<'de> Deserialize<'de> for StatRegistry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<StatRegistry, D::Error> {
+        let stats = Vec::<StatDefinition>::deserialize(deserializer)?;
+        StatRegistry::new(stats).map_err(|error| D::Error::custom(format!("{:?}", error)))
+    }
+}
@@ -1,4 +1,5 @@
 use super::*;
+use std::collections::HashSet;
 
 #[test]
 fn combat_works() {
@@ -34,8 +35,8 @@ fn winner_is_declared() {
     let mut attributes_b = CharacterAttributes::default();
     attributes_b.set(Constitution, 5);
     attributes_b.set(Strength, 1);
-    let mut combatant_a = CharacterBuilder::new(2, 8, &attributes_a).build();
-    let mut combatant_b = CharacterBuilder::new(2, 8, &attributes_b).build();
+    let mut combatant_a = CharacterBuilder::new(8, &attributes_a).build();
+    let mut combatant_b = CharacterBuilder::new(8, &attributes_b).build();
 
     // Act
     let winner_str;
@@ -59,6 +60,199 @@ fn winner_is_declared() {
     assert_eq!(winner_str, combatant_b.name());
 }
 
+#[test]
+fn recap_summarizes_combat() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 5);
+    attributes.set(Attribute::Strength, 2);
+    let mut player = CharacterBuilder::new(8, &attributes).build();
+    let mut goblin = MonsterBuilder::new("goblin", 1, 4).spawn();
+
+    // Act
+    let mut combat = Combat::new(&player, &goblin);
+    while Combat::can_combat(&player, &goblin) {
+        combat.apply_round(&mut player, &mut goblin);
+    }
+
+    // Assert
+    let recap = match combat.results {
+        Results::End { ref recap, .. } => recap.clone(),
+        _ => unreachable!(),
+    };
+    assert_eq!(recap.rounds, 2);
+    assert_eq!(recap.a.hits, 2);
+    assert_eq!(recap.a.damage_dealt, 4);
+    assert_eq!(recap.a.damage_taken, 2);
+    assert_eq!(recap.b.damage_taken, 4);
+    assert_eq!(recap.a.items_used, vec!["fist".to_owned()]);
+    let blow = recap.killing_blow.as_ref().unwrap();
+    assert_eq!(blow.by, CombatantId::A);
+    assert_eq!(blow.round, 2);
+    assert_eq!(
+        recap.narrate(),
+        "The fight lasted two rounds. You dealt 4 damage with two hits and zero misses, and \
+         took 2 damage. The goblin landed two hits and zero misses. You struck the killing blow \
+         with the fist in round 2."
+    );
+}
+
+#[test]
+fn recap_records_skills_and_consumables() {
+    // Arrange
+    let library = SkillLibrary::from_yaml(include_str!("../../data/txt/skills.yml")).unwrap();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    attributes.set(Attribute::Endurance, 5);
+    let mut player = CharacterBuilder::new(8, attributes)
+        .skill(library.get("Power Strike").unwrap())
+        .build();
+    let potion: Item = consumable("Potion", 1, vec![]).build().into();
+    let position = player.inventory.put(potion).unwrap();
+    let mut dummy = MonsterBuilder::new("Training Dummy", 0, 6).spawn();
+    let mut combat = Combat::new(&player, &dummy);
+
+    // Act
+    player.queue_consumable(position).unwrap();
+    combat.apply_round(&mut player, &mut dummy);
+    player.queue_skill("Power Strike").unwrap();
+    combat.apply_round(&mut player, &mut dummy);
+
+    // Assert
+    let recap = match combat.results {
+        Results::End { ref recap, .. } => recap.clone(),
+        _ => unreachable!(),
+    };
+    assert_eq!(recap.a.items_used, vec!["Potion", "Power Strike"]);
+    assert_eq!(recap.a.hits, 1);
+    let blow = recap.killing_blow.as_ref().unwrap();
+    assert_eq!(blow.weapon, "Power Strike");
+    assert_eq!(blow.damage, 6);
+    assert_eq!(blow.round, 2);
+    assert!(recap.narrate().ends_with("You struck the killing blow with Power Strike in round 2."));
+}
+
+#[test]
+fn recap_notes_when_both_sides_fall() {
+    // Arrange
+    let mut alice = CharacterBuilder::new(8, CharacterAttributes::default()).named("Alice").build();
+    let mut bob = CharacterBuilder::new(8, CharacterAttributes::default()).named("Bob").build();
+    alice.set_life(1);
+    bob.set_life(1);
+
+    // Act
+    let mut combat = Combat::new(&alice, &bob);
+    combat.apply_round(&mut alice, &mut bob);
+
+    // Assert
+    let recap = match combat.results {
+        Results::End { ref recap, .. } => recap.clone(),
+        _ => unreachable!(),
+    };
+    assert!(recap.draw);
+    assert!(recap.killing_blow.is_none());
+    assert!(recap.narrate().ends_with("You and Bob struck each other down in round 1."));
+}
+
+#[test]
+fn variety_avoids_recent_choices() {
+    use rand::{SeedableRng, StdRng};
+    let seed: &[usize] = &[1, 2, 3, 4];
+    let mut rng: StdRng = SeedableRng::from_seed(seed);
+    let options = ["a", "b", "c"];
+    let mut variety = Variety::new(2);
+
+    let picks: Vec<&str> = (0..30).map(|_| variety.pick(&options, &mut rng)).collect();
+
+    // Any three consecutive picks are all different
+    for window in picks.windows(3) {
+        assert!(window[0] != window[1] && window[1] != window[2] && window[0] != window[2]);
+    }
+}
+
+#[test]
+fn consecutive_rounds_are_narrated_differently() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 10);
+    let mut player = CharacterBuilder::new(8, &attributes).build();
+    let mut goblin = MonsterBuilder::new("goblin", 1, 10).spawn();
+    let mut combat = Combat::new(&player, &goblin).variety_window(4);
+
+    // Act
+    let mut logs = vec![];
+    while Combat::can_combat(&player, &goblin) {
+        if let Results::Round { ref log, .. } = *combat.apply_round(&mut player, &mut goblin) {
+            logs.push(log.clone());
+        }
+    }
+
+    // Assert
+    assert!(logs.len() > 2);
+    for pair in logs.windows(2) {
+        assert!(pair[0] != pair[1]);
+    }
+}
+
+#[test]
+fn swiftness_gives_more_actions() {
+    // Arrange
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 20);
+    let mut player = CharacterBuilder::new(8, &attributes).build();
+    let mut slow = MonsterBuilder::new("slug", 1, 20).spawn();
+    let mut fast = MonsterBuilder::new("wasp", 1, 20).swiftness(7).spawn();
+    let boots = equipment(
+        "Boots of Haste",
+        1,
+        Slot::Feet,
+        vec![ItemEffect::AttributeModifier(Attribute::Swiftness, 3)],
+    ).build();
+
+    // Act
+    let mut slow_combat = Combat::new(&player, &slow);
+    slow_combat.apply_round(&mut player, &mut slow);
+    let mut fast_combat = Combat::new(&player, &fast);
+    fast_combat.apply_round(&mut player, &mut fast);
+    player.equip(boots).unwrap();
+
+    // Assert
+    assert_eq!(slow_combat.recap.b.hits, 1);
+    assert_eq!(fast_combat.recap.b.hits, 3);
+    assert_eq!(player.action_buffer().duration_reserved(), 2);
+}
+
+#[test]
+fn action_formula_is_configurable() {
+    let formula = ActionFormula {
+        base: 2,
+        swiftness_per_action: 2,
+        minimum: 1,
+        maximum: 3,
+    };
+    let monster = MonsterBuilder::new("hydra", 1, 1).action_formula(formula).spawn();
+
+    assert_eq!(formula.actions(1), 2);
+    assert_eq!(formula.actions(3), 3);
+    assert_eq!(formula.actions(30), 3);
+    assert_eq!(monster.action_buffer().count(&Action::Attack), 2);
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Swiftness, 3);
+    let character = CharacterBuilder::new(8, &attributes).action_formula(formula).unwrap().build();
+    assert_eq!(character.actions_per_round(), formula.actions(3));
+
+    // Swiftness derived from the number of actions can't depend on it in turn
+    let stats = StatRegistry::new(vec![
+        StatDefinition::primary("ActionsPerRound", 2),
+        StatDefinition::derived("Swiftness", "ActionsPerRound * 3"),
+    ]).unwrap();
+    let builder = CharacterBuilder::new(8, &attributes).stats(&stats).unwrap();
+    match builder.action_formula(formula) {
+        Err(StatError::Cycle(_)) => {}
+        _ => panic!("expected a cycle"),
+    }
+}
+
 #[test]
 fn monster_can_be_built() {
     MonsterBuilder::new("name", 1, 3).difficulty(1).spawn();
@@ -66,13 +260,327 @@ fn monster_can_be_built() {
 
 #[test]
 fn no_duplicate_uids() {
-    /*
-    let monster = MonsterBuilder::new("m", 1, 2).difficulty(3).spawn();
-    let monster2 = MonsterBuilder::new("m2", 1, 2).difficulty(3).spawn();
-    let character = Character::new("c", hashmap![], 1);
-    let character2 = Character::new("c2", hashmap![], 1);
-    let equip = Item::Equipment(Equipment::BaseItem(BaseItem {}));
-    let equip2 = Item::Equipment(Equipment::BaseItem(BaseItem {}));
-    let consumable = Item::Consumable::new();
-    */
+    let builder = MonsterBuilder::new("m", 1, 2).difficulty(3);
+    let monster = builder.spawn();
+    let monster2 = builder.spawn();
+    let character = CharacterBuilder::new(8, CharacterAttributes::default()).build();
+    let character2 = Character::default();
+    let sword = equipment("Sword", 1, Slot::Hand, vec![]);
+    let equip: Item = sword.build().into();
+    let equip2: Item = sword.build().into();
+    let consumable: Item = consumable("Potion", 1, vec![]).build().into();
+    let bag: Item = container("Bag", 1, 2).build().into();
+
+    let ids: HashSet<EntityId> = vec![
+        monster.id(),
+        monster2.id(),
+        monster.spawn().id(),
+        character.id(),
+        character2.id(),
+        equip.id(),
+        equip2.id(),
+        consumable.id(),
+        bag.id(),
+    ].into_iter()
+        .collect();
+    assert_eq!(ids.len(), 9);
+}
+
+#[test]
+fn loaded_ids_are_not_reused() {
+    let sword = equipment("Sword", 1, Slot::Hand, vec![]).build();
+    let mut saved = ::serde_yaml::to_value(&sword).unwrap();
+    saved["id"] = 1_000_000.into();
+
+    let loaded: Equipment = ::serde_yaml::from_value(saved).unwrap();
+
+    assert_eq!(::serde_yaml::to_value(loaded.id()).unwrap(), 1_000_000);
+    assert!(equipment("Axe", 1, Slot::Hand, vec![]).build().id() > loaded.id());
+}
+
+#[test]
+fn items_can_be_found_by_id() {
+    let mut character = CharacterBuilder::new(4, CharacterAttributes::default()).build();
+    let sword = equipment("Sword", 1, Slot::Hand, vec![]).build();
+    let potion: Item = consumable("Potion", 1, vec![]).build().into();
+    let mut bag = container("Bag", 1, 2).build();
+    bag.put(potion.clone());
+    character.inventory.put(equipment("Dagger", 1, Slot::Hand, vec![]).build().into());
+    character.inventory.put(bag.into());
+    character.equip(sword.clone()).unwrap();
+
+    let (path, item) = character.inventory.find_by_id(potion.id()).unwrap();
+    assert_eq!(path, vec![1, 0]);
+    assert_eq!(item.name(), "Potion");
+    // Positions shift when items are taken, identifiers do not
+    character.inventory.take(0);
+    character.inventory.compact();
+    assert_eq!(character.inventory.find_by_id(potion.id()).unwrap().0, vec![0, 0]);
+    let slot = character.equipment().find_by_id(sword.id()).unwrap();
+    assert_eq!(character.equipment().at(slot).unwrap().name(), "Sword");
+    assert!(character.inventory.find_by_id(sword.id()).is_none());
+}
+
+#[test]
+fn skills_are_used_in_combat() {
+    // Arrange
+    let library = SkillLibrary::from_yaml(include_str!("../../data/txt/skills.yml")).unwrap();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    attributes.set(Attribute::Endurance, 5);
+    let mut character = CharacterBuilder::new(8, attributes)
+        .skill(library.get("Power Strike").unwrap())
+        .build();
+    let mut monster = MonsterBuilder::new("Training Dummy", 0, 100).spawn();
+    let mut combat = Combat::new(&character, &monster);
+
+    // Act
+    character.queue_skill("Power Strike").unwrap();
+    combat.apply_round(&mut character, &mut monster);
+    let after_skill = monster.life();
+    combat.apply_round(&mut character, &mut monster);
+
+    // Assert
+    assert_eq!(after_skill, 100 - 2 * 3);
+    assert_eq!(monster.life(), after_skill - 3);
+    assert_eq!(character.stamina(), 2);
+    assert_eq!(character.cooldown("Power Strike"), 1);
+}
+
+fn party_member(name: &str, constitution: i32) -> Character {
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, constitution);
+    CharacterBuilder::new(4, attributes).named(name).build()
+}
+
+#[test]
+fn party_keeps_marching_order_and_leader() {
+    // Arrange
+    let alice = party_member("Alice", 5);
+    let bob = party_member("Bob", 5);
+    let carol = party_member("Carol", 5);
+    let (alice_id, bob_id, carol_id) = (alice.id(), bob.id(), carol.id());
+    let mut party = Party::new(alice);
+    party.join(bob);
+    party.join(carol);
+
+    // Act
+    party.move_to(carol_id, 0);
+    let left = party.leave(alice_id);
+
+    // Assert
+    assert_eq!(left.map(|member| member.name()), Some("Alice".to_owned()));
+    let order: Vec<String> = party.members().iter().map(|member| member.name()).collect();
+    assert_eq!(order, vec!["Carol".to_owned(), "Bob".to_owned()]);
+    assert_eq!(party.leader().id(), carol_id);
+    assert!(party.set_leader(bob_id));
+    assert!(!party.set_leader(alice_id));
+    assert_eq!(party.definite_name(), "Bob's party");
+    assert!(party.leave(carol_id).is_some());
+    assert!(party.leave(bob_id).is_none());
+}
+
+#[test]
+fn party_inventory_policy() {
+    let alice = party_member("Alice", 5);
+    let bob = party_member("Bob", 5);
+    let (alice_id, bob_id) = (alice.id(), bob.id());
+    let mut party = Party::new(alice).share_inventory(10);
+    party.join(bob);
+    let potion: Item = consumable("Potion", 1, vec![]).build().into();
+
+    party.inventory_of_mut(alice_id).unwrap().put(potion).unwrap();
+
+    assert_eq!(party.inventory_policy(), InventoryPolicy::Shared);
+    assert_eq!(party.inventory_of(bob_id).unwrap().iter().count(), 1);
+    assert_eq!(party.member(bob_id).unwrap().inventory.iter().count(), 0);
+    assert!(party.inventory_of(EntityId::new()).is_none());
+}
+
+#[test]
+fn party_fights_together() {
+    // Arrange
+    let mut party = Party::new(party_member("Alice", 3));
+    party.join(party_member("Bob", 5));
+    let mut monster = MonsterBuilder::new("Troll", 4, 10).spawn();
+    let mut combat = Combat::new(&party, &monster);
+
+    // Act
+    combat.apply_round(&mut party, &mut monster);
+
+    // Assert
+    // Alice in front takes 3 damage and falls, Bob takes the remaining 1
+    assert_eq!(party.living().len(), 1);
+    assert_eq!(party.front().map(|member| member.name()), Some("Bob".to_owned()));
+    assert_eq!(party.life(), 4);
+    // Both members attacked
+    assert_eq!(monster.life(), 8);
+    assert!(!party.all_dead());
+
+    while Combat::can_combat(&party, &monster) {
+        combat.apply_round(&mut party, &mut monster);
+    }
+    assert!(party.all_dead());
+    assert!(combat.has_ended());
+}
+
+#[test]
+fn skills_buff_and_debuff_in_combat() {
+    // Arrange
+    let library = SkillLibrary::from_yaml(include_str!("../../data/txt/skills.yml")).unwrap();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 4);
+    attributes.set(Attribute::Constitution, 50);
+    attributes.set(Attribute::Endurance, 10);
+    attributes.set(Attribute::Swiftness, 4);
+    let mut a = CharacterBuilder::new(8, &attributes)
+        .named("Alice")
+        .skill(library.get("Battle Cry").unwrap())
+        .build();
+    let mut b = CharacterBuilder::new(8, &attributes)
+        .named("Bob")
+        .skill(library.get("Hamstring").unwrap())
+        .build();
+    let mut combat = Combat::new(&a, &b);
+
+    // Act
+    a.queue_skill("Battle Cry").unwrap();
+    b.queue_skill("Hamstring").unwrap();
+    combat.apply_round(&mut a, &mut b);
+
+    // Assert
+    // Battle Cry lasts for the next round too
+    assert_eq!(a.attribute(&Attribute::Strength), 6);
+    assert_eq!(a.attribute(&Attribute::Swiftness), 2);
+    assert_eq!(a.timed_modifiers().len(), 2);
+    // Hamstring has not been counted down yet
+    assert_eq!(a.timed_modifiers()[1].rounds, 3);
+    combat.apply_round(&mut a, &mut b);
+    assert_eq!(a.attribute(&Attribute::Strength), 4);
+    assert_eq!(a.timed_modifiers().len(), 1);
+    assert_eq!(a.timed_modifiers()[0].rounds, 2);
+}
+
+#[test]
+fn formulas_are_parsed_and_evaluated() {
+    let formula = Formula::parse("max(Strength * 2 - (Luck + 1) / 2, -3)").unwrap();
+    let stat = |name: &str| match name {
+        "Strength" => 4,
+        "Luck" => 5,
+        _ => 0,
+    };
+
+    assert_eq!(formula.evaluate(&stat), 5);
+    assert_eq!(formula.references(), vec!["Strength", "Luck"]);
+    assert_eq!(Formula::parse("-Strength").unwrap().evaluate(&stat), -4);
+    assert_eq!(Formula::parse("Strength / 0").unwrap().evaluate(&stat), 0);
+    assert!(Formula::parse("Strength +").is_err());
+    assert!(Formula::parse("sqrt(Luck)").is_err());
+    assert!(Formula::parse("(Luck").is_err());
+}
+
+#[test]
+fn invalid_stat_definitions_are_rejected() {
+    let unknown = StatRegistry::new(vec![StatDefinition::derived("Damage", "Strength")]);
+    let cycle = StatRegistry::new(vec![
+        StatDefinition::derived("A", "B + 1"),
+        StatDefinition::derived("B", "A * 2"),
+    ]);
+    let duplicate = StatRegistry::new(vec![
+        StatDefinition::primary("Luck", 0),
+        StatDefinition::primary("Luck", 1),
+    ]);
+
+    match unknown {
+        Err(StatError::UnknownReference { stat, reference }) => {
+            assert_eq!((stat.as_str(), reference.as_str()), ("Damage", "Strength"))
+        }
+        other => panic!("unexpected {:?}", other),
+    }
+    match cycle {
+        Err(StatError::Cycle(stat)) => assert_eq!(stat, "A"),
+        other => panic!("unexpected {:?}", other),
+    }
+    match duplicate {
+        Err(StatError::Duplicate(stat)) => assert_eq!(stat, "Luck"),
+        other => panic!("unexpected {:?}", other),
+    }
+}
+
+#[test]
+fn items_modify_registered_stats() {
+    // Arrange
+    let stats = StatRegistry::from_yaml(include_str!("../../data/txt/stats.yml")).unwrap();
+    let items: Vec<Equipment> =
+        ::serde_yaml::from_str(include_str!("../../data/txt/base_items.yml")).unwrap();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Strength, 3);
+    attributes.set(Attribute::Constitution, 4);
+    let mut character = CharacterBuilder::new(8, &attributes)
+        .stats(&stats)
+        .unwrap()
+        .stat("Luck", 1)
+        .build();
+    let critical_chance = character.stat("CriticalChance");
+    let frame = equipment(
+        "Pack Frame",
+        1,
+        Slot::Torso,
+        vec![ItemEffect::StatModifier("CarryLimit".to_owned(), 10)],
+    ).build();
+
+    // Act
+    for item in items {
+        character.equip(item).unwrap();
+    }
+    character.equip(frame).unwrap();
+
+    // Assert
+    assert_eq!(critical_chance, Some(5 + 2));
+    assert_eq!(character.damage(), 2 + 3 + 1);
+    assert_eq!(character.stat("Damage"), Some(character.damage()));
+    assert_eq!(character.max_life(), 9);
+    assert_eq!(character.stat("MaxLife"), Some(9));
+    assert_eq!(character.stat("Luck"), Some(4));
+    assert_eq!(character.attribute(&Attribute::Swiftness), 2);
+    assert_eq!(character.stat("CriticalChance"), Some(5 + 8 + 1));
+    assert_eq!(character.stat("CarryLimit"), Some(40));
+    assert_eq!(character.load().carry_limit, 40);
+    assert_eq!(character.stat("ActionsPerRound"), Some(1));
+    assert_eq!(character.stat("Mana"), None);
+}
+
+#[test]
+fn modifiers_apply_to_any_stat() {
+    // Arrange
+    let stats = StatRegistry::from_yaml(include_str!("../../data/txt/stats.yml")).unwrap();
+    let mut attributes = CharacterAttributes::default();
+    attributes.set(Attribute::Constitution, 4);
+    let mut character = CharacterBuilder::new(8, &attributes)
+        .stats(&stats)
+        .unwrap()
+        .stat("Luck", 2)
+        .build();
+    let amulet = equipment(
+        "Amulet of Vigor",
+        1,
+        Slot::Head,
+        vec![ItemEffect::Modifier(Modifier::percent("MaxLife", 50))],
+    ).build();
+
+    // Act
+    character.equip(amulet).unwrap();
+    character.add_timed_modifier(Modifier::flat("Luck", 3), 2, "Blessing");
+
+    // Assert
+    assert_eq!(character.max_life(), 6);
+    assert_eq!(
+        character.stat_breakdown("MaxLife").unwrap().to_string(),
+        "MaxLife 6: base 4, +50% from Amulet of Vigor"
+    );
+    assert_eq!(character.stat("Luck"), Some(5));
+    character.tick_modifiers();
+    character.tick_modifiers();
+    assert_eq!(character.stat("Luck"), Some(2));
+    assert!(character.stat_breakdown("Mana").is_none());
 }